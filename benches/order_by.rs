@@ -0,0 +1,61 @@
+//! Compares `order_by`, which computes each key once, with recomputing the key on every comparison.
+//!
+//! Run with `cargo bench --bench order_by`.
+
+use linq::iter::Enumerable;
+use std::cell::Cell;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const LEN: usize = 100_000;
+const ROUNDS: u32 = 5;
+
+/// A key which is costly to compute, like a date parsed from text.
+fn parse_key(text: &str, calls: &Cell<usize>) -> (u32, u32) {
+    calls.set(calls.get() + 1);
+    let mut parts = text.split('-').map(|part| part.parse::<u32>().unwrap());
+    (parts.next().unwrap(), parts.next().unwrap())
+}
+
+/// Runs `sort` several times, returning the fastest time and the key calls of one run.
+fn measure<F: FnMut(&Cell<usize>) -> usize>(mut sort: F) -> (Duration, usize) {
+    let mut best = Duration::MAX;
+    let mut calls = 0;
+    for _ in 0..ROUNDS {
+        let counter = Cell::new(0);
+        let start = Instant::now();
+        black_box(sort(&counter));
+        best = best.min(start.elapsed());
+        calls = counter.get();
+    }
+    (best, calls)
+}
+
+fn report(name: &str, (time, calls): (Duration, usize)) {
+    println!("{:<24} {:>10.2?} {:>12} key calls", name, time, calls);
+}
+
+fn main() {
+    let x: Vec<String> = (0..LEN)
+        .map(|p| format!("{}-{}", (p * 7919) % 1000, (p * 104_729) % 1000))
+        .collect();
+
+    let cached = measure(|calls| x.iter().order_by(|p| parse_key(p, calls)).count());
+    let uncached = measure(|calls| x.iter().order_by_uncached(|p| parse_key(p, calls)).count());
+    let sort_by_key = measure(|calls| {
+        let mut y: Vec<_> = x.iter().collect();
+        y.sort_by_key(|p| parse_key(p, calls));
+        y.len()
+    });
+    let first = measure(|calls| x.iter().order_by(|p| parse_key(p, calls)).take(10).count());
+
+    println!("sorting {} elements by a parsed key", LEN);
+    report("order_by", cached);
+    report("order_by_uncached", uncached);
+    report("Vec::sort_by_key", sort_by_key);
+    report("order_by, first 10", first);
+
+    assert_eq!(cached.1, LEN);
+    assert_eq!(first.1, LEN);
+    assert!(uncached.1 > LEN && sort_by_key.1 > LEN);
+}
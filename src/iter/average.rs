@@ -1,5 +1,8 @@
 pub trait Average<A = Self>: Sized {
     fn average<I: Iterator<Item = A>>(iter: I) -> Self;
+
+    /// Computes the average from the sum of `count` values.
+    fn from_sum(sum: A, count: usize) -> Self;
 }
 
 macro_rules! average_for_types {
@@ -9,7 +12,11 @@ macro_rules! average_for_types {
                 let sums = iter
                     .enumerate()
                     .fold((0, Self::default()), |acc, v| (acc.0 + 1, acc.1 + v.1));
-                sums.1 / (sums.0 as $x)
+                Self::from_sum(sums.1, sums.0)
+            }
+
+            fn from_sum(sum: Self, count: usize) -> Self {
+                sum / (count as $x)
             }
         }
         )*}
@@ -10,11 +10,67 @@ where
     iter.chain(other)
 }
 
+pub type ConcateAllIterator<I, U> = std::iter::Chain<I, std::iter::Flatten<U>>;
+
+pub fn concate_all<I: Iterator, U>(iter: I, others: U) -> ConcateAllIterator<I, U::IntoIter>
+where
+    U: IntoIterator,
+    U::Item: IntoIterator<Item = I::Item>,
+{
+    iter.chain(others.into_iter().flatten())
+}
+
+pub type AppendIterator<I, T> = std::iter::Chain<I, std::iter::Once<T>>;
+
+pub fn append<I: Iterator>(iter: I, item: I::Item) -> AppendIterator<I, I::Item> {
+    iter.chain(std::iter::once(item))
+}
+
+pub type PrependIterator<I, T> = std::iter::Chain<std::iter::Once<T>, I>;
+
+pub fn prepend<I: Iterator>(iter: I, item: I::Item) -> PrependIterator<I, I::Item> {
+    std::iter::once(item).chain(iter)
+}
+
+#[derive(Clone)]
+pub struct DefaultIfEmptyIterator<I: Iterator> {
+    source: std::iter::Fuse<I>,
+    default: Option<I::Item>,
+}
+
+impl<I: Iterator> Iterator for DefaultIfEmptyIterator<I> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.source.next() {
+            Some(item) => {
+                self.default = None;
+                Some(item)
+            }
+            None => self.default.take(),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.source.size_hint();
+        match self.default {
+            Some(_) => (std::cmp::max(lower, 1), upper.map(|x| std::cmp::max(x, 1))),
+            None => (lower, upper),
+        }
+    }
+}
+
+pub fn default_if_empty<I: Iterator>(iter: I, value: I::Item) -> DefaultIfEmptyIterator<I> {
+    DefaultIfEmptyIterator {
+        source: iter.fuse(),
+        default: Some(value),
+    }
+}
+
 pub type WhereIterator<I, P> = std::iter::Filter<I, P>;
 
 pub fn where_by<I: Iterator, P>(iter: I, predicate: P) -> WhereIterator<I, P>
 where
-    I: Sized,
     P: FnMut(&I::Item) -> bool,
 {
     iter.filter(predicate)
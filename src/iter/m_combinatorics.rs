@@ -0,0 +1,257 @@
+/// Yields every `k`-element selection of the buffered source as index vectors, in lexicographic order.
+///
+/// With `repeat`, the same index may be selected several times (combinations with replacement).
+/// `size_hint` is exact while the number of selections fits in a `usize`; otherwise it is `(usize::MAX, None)`.
+#[derive(Clone)]
+pub struct CombinationsIterator<T> {
+    pool: Vec<T>,
+    indices: Vec<usize>,
+    repeat: bool,
+    remaining: Option<usize>,
+}
+
+impl<T> CombinationsIterator<T> {
+    /// Moves `indices` to the next selection, returning `false` once the last one has been passed.
+    fn advance(&mut self) -> bool {
+        let n = self.pool.len();
+        let k = self.indices.len();
+        // Find the rightmost index which can still be increased.
+        let position = (0..k).rev().find(|i| {
+            if self.repeat {
+                self.indices[*i] < n - 1
+            } else {
+                self.indices[*i] < n - k + i
+            }
+        });
+        let i = match position {
+            Some(i) => i,
+            None => return false,
+        };
+        self.indices[i] += 1;
+        for j in i + 1..k {
+            self.indices[j] = if self.repeat {
+                self.indices[i]
+            } else {
+                self.indices[j - 1] + 1
+            };
+        }
+        true
+    }
+}
+
+impl<T: Clone> Iterator for CombinationsIterator<T> {
+    type Item = Vec<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == Some(0) {
+            return None;
+        }
+        let result = self.indices.iter().map(|i| self.pool[*i].clone()).collect();
+        self.remaining = self.remaining.map(|remaining| remaining - 1);
+        if self.remaining != Some(0) && !self.advance() {
+            self.remaining = Some(0);
+        }
+        Some(result)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.remaining {
+            Some(remaining) => (remaining, Some(remaining)),
+            None => (usize::MAX, None),
+        }
+    }
+}
+
+impl<T: Clone> std::iter::FusedIterator for CombinationsIterator<T> {}
+
+pub fn combinations<I: Iterator>(iter: I, k: usize) -> CombinationsIterator<I::Item> {
+    let pool: Vec<_> = iter.collect();
+    if k > pool.len() {
+        return CombinationsIterator {
+            pool,
+            indices: Vec::new(),
+            repeat: false,
+            remaining: Some(0),
+        };
+    }
+    let remaining = binomial(pool.len(), k);
+    CombinationsIterator {
+        pool,
+        indices: (0..k).collect(),
+        repeat: false,
+        remaining,
+    }
+}
+
+pub fn combinations_with_replacement<I: Iterator>(
+    iter: I,
+    k: usize,
+) -> CombinationsIterator<I::Item> {
+    let pool: Vec<_> = iter.collect();
+    if pool.is_empty() {
+        // Only the empty selection can be drawn from an empty pool.
+        return CombinationsIterator {
+            pool,
+            indices: Vec::new(),
+            repeat: true,
+            remaining: Some(if k == 0 { 1 } else { 0 }),
+        };
+    }
+    let remaining = (pool.len() - 1).checked_add(k).and_then(|n| binomial(n, k));
+    CombinationsIterator {
+        pool,
+        indices: vec![0; k],
+        repeat: true,
+        remaining,
+    }
+}
+
+/// Yields every ordered `k`-element selection of the buffered source, in lexicographic index order.
+///
+/// `size_hint` is exact while the number of selections fits in a `usize`; otherwise it is `(usize::MAX, None)`.
+#[derive(Clone)]
+pub struct PermutationsIterator<T> {
+    pool: Vec<T>,
+    indices: Vec<usize>,
+    /// Marks the pool positions in `indices`, kept between steps to avoid an allocation per permutation.
+    used: Vec<bool>,
+    remaining: Option<usize>,
+}
+
+impl<T> PermutationsIterator<T> {
+    /// Moves `indices` to the next selection, returning `false` once the last one has been passed.
+    fn advance(&mut self) -> bool {
+        let n = self.pool.len();
+        let k = self.indices.len();
+        // Advance the rightmost position to the next unused index, then fill the rest with the smallest unused.
+        let used = &mut self.used;
+        for i in (0..k).rev() {
+            let current = self.indices[i];
+            used[current] = false;
+            if let Some(next) = (current + 1..n).find(|x| !used[*x]) {
+                self.indices[i] = next;
+                used[next] = true;
+                for j in i + 1..k {
+                    let next = (0..n).find(|x| !used[*x]).unwrap();
+                    self.indices[j] = next;
+                    used[next] = true;
+                }
+                return true;
+            }
+        }
+        false
+    }
+}
+
+impl<T: Clone> Iterator for PermutationsIterator<T> {
+    type Item = Vec<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == Some(0) {
+            return None;
+        }
+        let result = self.indices.iter().map(|i| self.pool[*i].clone()).collect();
+        self.remaining = self.remaining.map(|remaining| remaining - 1);
+        if self.remaining != Some(0) && !self.advance() {
+            self.remaining = Some(0);
+        }
+        Some(result)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.remaining {
+            Some(remaining) => (remaining, Some(remaining)),
+            None => (usize::MAX, None),
+        }
+    }
+}
+
+impl<T: Clone> std::iter::FusedIterator for PermutationsIterator<T> {}
+
+pub fn permutations<I: Iterator>(iter: I, k: usize) -> PermutationsIterator<I::Item> {
+    let pool: Vec<_> = iter.collect();
+    let n = pool.len();
+    if k > n {
+        return PermutationsIterator {
+            pool,
+            indices: Vec::new(),
+            used: Vec::new(),
+            remaining: Some(0),
+        };
+    }
+    let remaining = (n - k + 1..=n).try_fold(1usize, |acc, x| acc.checked_mul(x));
+    PermutationsIterator {
+        pool,
+        indices: (0..k).collect(),
+        used: (0..n).map(|i| i < k).collect(),
+        remaining,
+    }
+}
+
+/// Yields every subset of the buffered source, by increasing size and then in lexicographic index order.
+///
+/// `size_hint` is exact while the number of subsets fits in a `usize`; otherwise it is `(usize::MAX, None)`.
+#[derive(Clone)]
+pub struct PowersetIterator<T> {
+    combinations: CombinationsIterator<T>,
+    size: usize,
+}
+
+impl<T: Clone> Iterator for PowersetIterator<T> {
+    type Item = Vec<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(subset) = self.combinations.next() {
+                return Some(subset);
+            }
+            let pool = &self.combinations.pool;
+            if self.size >= pool.len() {
+                return None;
+            }
+            self.size += 1;
+            self.combinations = CombinationsIterator {
+                remaining: binomial(pool.len(), self.size),
+                pool: std::mem::take(&mut self.combinations.pool),
+                indices: (0..self.size).collect(),
+                repeat: false,
+            };
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = self.combinations.pool.len();
+        let remaining = self.combinations.remaining.and_then(|current| {
+            (self.size + 1..=n).try_fold(current, |acc, k| acc.checked_add(binomial(n, k)?))
+        });
+        match remaining {
+            Some(remaining) => (remaining, Some(remaining)),
+            None => (usize::MAX, None),
+        }
+    }
+}
+
+impl<T: Clone> std::iter::FusedIterator for PowersetIterator<T> {}
+
+pub fn powerset<I: Iterator>(iter: I) -> PowersetIterator<I::Item> {
+    PowersetIterator {
+        combinations: combinations(iter, 0),
+        size: 0,
+    }
+}
+
+/// Computes `n` choose `k`, or `None` when it does not fit in a `usize`.
+fn binomial(n: usize, k: usize) -> Option<usize> {
+    if k > n {
+        return Some(0);
+    }
+    let k = std::cmp::min(k, n - k);
+    let mut result: u128 = 1;
+    for i in 0..k {
+        result = result * (n - i) as u128 / (i + 1) as u128;
+        if result > usize::MAX as u128 {
+            return None;
+        }
+    }
+    Some(result as usize)
+}
@@ -29,6 +29,17 @@ where
             }
         }
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.source.size_hint().1)
+    }
+}
+
+impl<I> std::iter::FusedIterator for DistinctIterator<I>
+where
+    I: std::iter::FusedIterator,
+    I::Item: Eq + std::hash::Hash + Copy,
+{
 }
 
 pub fn distinct<I>(iter: I) -> DistinctIterator<I>
@@ -38,7 +49,7 @@ where
 {
     let hash_map = std::collections::HashSet::new();
     DistinctIterator {
-        hash_map: hash_map,
+        hash_map,
         source: iter,
     }
 }
@@ -1,7 +1,34 @@
-use super::{average::Average, m_builtin, m_distinct, m_method, m_order_by, m_select, m_union};
-use m_builtin::{ConcateIterator, ReverseIterator, SelectIterator, WhereIterator};
-use m_order_by::OrderedIterator;
+use super::{
+    average::Average, m_builtin, m_combinatorics, m_distinct, m_external, m_float, m_histogram,
+    m_join, m_merge, m_method, m_order_by, m_radix, m_random, m_scan, m_select, m_sketch,
+    m_sliding, m_sorted, m_statistics, m_topological, m_traverse, m_tree, m_union, m_window, m_zip,
+    spill,
+};
+use m_builtin::{
+    AppendIterator, ConcateAllIterator, ConcateIterator, DefaultIfEmptyIterator, PrependIterator,
+    ReverseIterator, SelectIterator, WhereIterator,
+};
+use m_combinatorics::{CombinationsIterator, PermutationsIterator, PowersetIterator};
+use m_external::ExternalOrderedIterator;
+use m_float::{DistinctFloatIterator, Float, FloatKey};
+use m_histogram::{Bin, Binning, Bucket};
+use m_join::GraceJoinIterator;
+use m_merge::MergeSortedIterator;
+use m_method::SequenceDifference;
+use m_order_by::{BorrowedKey, CachedKey, OrderedIterator, UncachedKey};
+use m_radix::{RadixKey, RadixOrderedIterator};
+use m_random::{SampleIterator, ShuffleIterator};
+use m_scan::{LagIterator, LeadIterator, PairwiseIterator, ScanAggregateIterator};
 use m_select::{SelectManyIterator, SelectManySingleIterator};
+use m_sliding::{MovingAverageIterator, MovingExtremumIterator, MovingSumIterator, SlidingWindow};
+use m_sorted::{MergeJoinIterator, SetOperation, SortedDistinctIterator, SortedSetIterator};
+use m_statistics::{Interpolation, LinearRegression, VarianceKind};
+use m_topological::{TopologicalSortError, TopologicalSortIterator};
+use m_traverse::TraverseIterator;
+use m_tree::{Tree, TreeError};
+use m_window::PartitionWindow;
+use m_zip::{Zip3Iterator, ZipLongestIterator, ZipWithIterator};
+use spill::Spill;
 
 /// `Enumerable` is an extension of `Iterator`. It brings LINQ methods to `Iterator`.
 ///
@@ -115,6 +142,9 @@ pub trait Enumerable: Iterator {
 
     /// Sorts the elements of a sequence in ascending order according to a key.
     ///
+    /// The key selector is called exactly once per element, and the keys are stored until the elements are yielded.
+    /// See `order_by_uncached` for keys which are cheaper to recompute than to store.
+    ///
     /// # Examples
     ///
     /// ```
@@ -128,7 +158,7 @@ pub trait Enumerable: Iterator {
     ///
     /// assert_eq!(e, y);
     /// ```
-    fn order_by<TKey, F>(self, f: F) -> OrderedIterator<Self::Item>
+    fn order_by<TKey, F>(self, f: F) -> OrderedIterator<Self::Item, CachedKey<F>>
     where
         Self: Sized,
         TKey: Ord,
@@ -139,6 +169,8 @@ pub trait Enumerable: Iterator {
 
     /// Sorts the elements of a sequence in descending order by using a specified comparer.
     ///
+    /// Elements with equal keys come in reverse source order, so the result is the exact reverse of `order_by`.
+    ///
     /// # Examples
     ///
     /// ```
@@ -156,7 +188,7 @@ pub trait Enumerable: Iterator {
     ///
     /// assert_eq!(e, y);
     /// ```
-    fn order_by_descending<TKey, F>(self, f: F) -> OrderedIterator<Self::Item>
+    fn order_by_descending<TKey, F>(self, f: F) -> OrderedIterator<Self::Item, CachedKey<F>>
     where
         Self: Sized,
         TKey: Ord,
@@ -165,6 +197,313 @@ pub trait Enumerable: Iterator {
         m_order_by::order_by(self, f, true)
     }
 
+    /// Sorts the elements of a sequence in ascending order according to a key,
+    /// calling the key selector on every comparison instead of storing the keys.
+    ///
+    /// `order_by` calls the selector once per element, which is better for expensive keys.
+    /// This variant saves the memory of the keys when they are cheap to compute, like a field copy.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use linq::iter::Enumerable;
+    ///
+    /// let x = vec![(2, 'b'), (1, 'a'), (3, 'c'), (1, 'd')];
+    /// let e: Vec<char> = x.into_iter().order_by_uncached(|p| p.0).select(|p| p.1).collect();
+    /// assert_eq!(e, vec!['a', 'd', 'b', 'c']);
+    /// ```
+    fn order_by_uncached<TKey, F>(self, f: F) -> OrderedIterator<Self::Item, UncachedKey<F>>
+    where
+        Self: Sized,
+        TKey: Ord,
+        F: Fn(&Self::Item) -> TKey,
+    {
+        m_order_by::order_by_uncached(self, f, false)
+    }
+
+    /// Sorts the elements of a sequence in descending order according to a key,
+    /// calling the key selector on every comparison instead of storing the keys.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use linq::iter::Enumerable;
+    ///
+    /// let x = vec![(2, 'b'), (1, 'a'), (3, 'c'), (1, 'd')];
+    /// let e: Vec<char> = x
+    ///     .into_iter()
+    ///     .order_by_descending_uncached(|p| p.0)
+    ///     .select(|p| p.1)
+    ///     .collect();
+    /// assert_eq!(e, vec!['c', 'b', 'd', 'a']);
+    /// ```
+    fn order_by_descending_uncached<TKey, F>(
+        self,
+        f: F,
+    ) -> OrderedIterator<Self::Item, UncachedKey<F>>
+    where
+        Self: Sized,
+        TKey: Ord,
+        F: Fn(&Self::Item) -> TKey,
+    {
+        m_order_by::order_by_uncached(self, f, true)
+    }
+
+    /// Sorts the elements of a sequence in ascending order according to a key borrowed from each element.
+    ///
+    /// Unlike `order_by`, the key isn't cloned or stored, so sorting by a `String` field doesn't allocate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use linq::iter::Enumerable;
+    ///
+    /// let x = vec![(String::from("carol"), 3), (String::from("alice"), 1), (String::from("bob"), 2)];
+    /// let e: Vec<i32> = x.iter().order_by_ref(|p| &p.0).select(|p| p.1).collect();
+    /// assert_eq!(e, vec![1, 2, 3]);
+    /// ```
+    fn order_by_ref<TKey, F>(self, f: F) -> OrderedIterator<Self::Item, BorrowedKey<F>>
+    where
+        Self: Sized,
+        TKey: Ord + ?Sized,
+        F: for<'a> Fn(&'a Self::Item) -> &'a TKey,
+    {
+        m_order_by::order_by_ref(self, f, false)
+    }
+
+    /// Sorts the elements of a sequence in descending order according to a key borrowed from each element.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use linq::iter::Enumerable;
+    ///
+    /// let x = vec![String::from("b"), String::from("c"), String::from("a")];
+    /// let e: Vec<String> = x.into_iter().order_by_ref_descending(|p| p.as_str()).collect();
+    /// assert_eq!(e, vec!["c", "b", "a"]);
+    /// ```
+    fn order_by_ref_descending<TKey, F>(self, f: F) -> OrderedIterator<Self::Item, BorrowedKey<F>>
+    where
+        Self: Sized,
+        TKey: Ord + ?Sized,
+        F: for<'a> Fn(&'a Self::Item) -> &'a TKey,
+    {
+        m_order_by::order_by_ref(self, f, true)
+    }
+
+    /// Sorts the elements of a sequence in ascending order according to an integer key, by LSD radix sort.
+    ///
+    /// It runs in `O(n * RadixKey::BYTES)` time, and it keeps the same stable ordering as `order_by`.
+    /// `RadixKey` is implemented for primitive integers, `bool`, `char` and tuples of them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use linq::iter::Enumerable;
+    ///
+    /// let x = vec![(3u32, 'a'), (1, 'b'), (2, 'c'), (1, 'd')];
+    /// let e: Vec<char> = x.into_iter().order_by_radix(|p| p.0).select(|p| p.1).collect();
+    /// assert_eq!(e, vec!['b', 'd', 'c', 'a']);
+    /// ```
+    fn order_by_radix<TKey, F>(self, f: F) -> RadixOrderedIterator<Self::Item>
+    where
+        Self: Sized,
+        TKey: RadixKey,
+        F: Fn(&Self::Item) -> TKey,
+    {
+        m_radix::order_by_radix(self, f, false)
+    }
+
+    /// Sorts the elements of a sequence in descending order according to an integer key, by LSD radix sort.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use linq::iter::Enumerable;
+    ///
+    /// let x = vec![-5i64, 3, 0, -1];
+    /// let e: Vec<i64> = x.into_iter().order_by_radix_descending(|p| *p).collect();
+    /// assert_eq!(e, vec![3, 0, -1, -5]);
+    /// ```
+    fn order_by_radix_descending<TKey, F>(self, f: F) -> RadixOrderedIterator<Self::Item>
+    where
+        Self: Sized,
+        TKey: RadixKey,
+        F: Fn(&Self::Item) -> TKey,
+    {
+        m_radix::order_by_radix(self, f, true)
+    }
+
+    /// Sorts the elements of a sequence in ascending order according to a float key.
+    ///
+    /// The keys are compared by IEEE 754 `totalOrder`, like `f64::total_cmp`, so positive NaNs come last.
+    /// Use `order_by` with `TotalFloat::with_nan` to choose where NaNs are placed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use linq::iter::Enumerable;
+    ///
+    /// let x = vec![2.5, f64::NAN, -1.0, 0.5];
+    /// let e: Vec<f64> = x.into_iter().order_by_float(|p| *p).collect();
+    /// assert_eq!(e[..3], [-1.0, 0.5, 2.5]);
+    /// assert!(e[3].is_nan());
+    /// ```
+    fn order_by_float<TKey, F>(self, f: F) -> OrderedIterator<Self::Item, FloatKey<F>>
+    where
+        Self: Sized,
+        TKey: Float,
+        F: Fn(&Self::Item) -> TKey,
+    {
+        m_float::order_by_float(self, f, false)
+    }
+
+    /// Sorts the elements of a sequence in descending order according to a float key, by IEEE 754 `totalOrder`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use linq::iter::Enumerable;
+    ///
+    /// let x = vec![("a", 0.5f32), ("b", 2.5), ("c", -1.0)];
+    /// let e: Vec<&str> = x
+    ///     .into_iter()
+    ///     .order_by_float_descending(|p| p.1)
+    ///     .select(|p| p.0)
+    ///     .collect();
+    /// assert_eq!(e, vec!["b", "a", "c"]);
+    /// ```
+    fn order_by_float_descending<TKey, F>(self, f: F) -> OrderedIterator<Self::Item, FloatKey<F>>
+    where
+        Self: Sized,
+        TKey: Float,
+        F: Fn(&Self::Item) -> TKey,
+    {
+        m_float::order_by_float(self, f, true)
+    }
+
+    /// Sorts the elements of a sequence in ascending order according to a key, spilling sorted runs to temporary files,
+    /// so sequences larger than memory can be sorted.
+    ///
+    /// The elements are encoded by `Spill` as they arrive. Whenever the buffered elements reach `memory_budget`
+    /// bytes, they are sorted and written to a new file in `temp_dir`. Each element counts its encoded bytes
+    /// plus the inline size of its key and buffer, but not heap memory owned by the key.
+    /// A run holds at least 16 elements, however small the budget.
+    /// The runs are merged lazily, and the files are removed when the returned iterator is dropped.
+    /// The ordering is stable, like `order_by`.
+    ///
+    /// The key selector is called once more for each element while merging. Reading a run can fail,
+    /// so the iterator yields `io::Result`, and it stops after the first error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use linq::iter::Enumerable;
+    ///
+    /// let x = (0..1000u32).map(|p| (p * 7919) % 1000);
+    /// let e: Vec<u32> = x
+    ///     .order_by_external(|p| *p, 256, std::env::temp_dir())
+    ///     .unwrap()
+    ///     .collect::<std::io::Result<_>>()
+    ///     .unwrap();
+    /// assert_eq!(e, (0..1000).collect::<Vec<u32>>());
+    /// ```
+    fn order_by_external<TKey, F, P>(
+        self,
+        f: F,
+        memory_budget: usize,
+        temp_dir: P,
+    ) -> std::io::Result<ExternalOrderedIterator<Self::Item, TKey, F>>
+    where
+        Self: Sized,
+        Self::Item: Spill,
+        TKey: Ord,
+        F: Fn(&Self::Item) -> TKey,
+        P: AsRef<std::path::Path>,
+    {
+        m_external::order_by_external(self, f, memory_budget, temp_dir.as_ref(), false)
+    }
+
+    /// Sorts the elements of a sequence in descending order according to a key, spilling sorted runs to temporary files.
+    ///
+    /// See `order_by_external` for how `memory_budget` and `temp_dir` are used.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use linq::iter::Enumerable;
+    ///
+    /// let x = vec![String::from("b"), String::from("c"), String::from("a")];
+    /// let e: Vec<String> = x
+    ///     .into_iter()
+    ///     .order_by_external_descending(|p| p.clone(), 1, std::env::temp_dir())
+    ///     .unwrap()
+    ///     .collect::<std::io::Result<_>>()
+    ///     .unwrap();
+    /// assert_eq!(e, vec!["c", "b", "a"]);
+    /// ```
+    fn order_by_external_descending<TKey, F, P>(
+        self,
+        f: F,
+        memory_budget: usize,
+        temp_dir: P,
+    ) -> std::io::Result<ExternalOrderedIterator<Self::Item, TKey, F>>
+    where
+        Self: Sized,
+        Self::Item: Spill,
+        TKey: Ord,
+        F: Fn(&Self::Item) -> TKey,
+        P: AsRef<std::path::Path>,
+    {
+        m_external::order_by_external(self, f, memory_budget, temp_dir.as_ref(), true)
+    }
+
+    /// Returns the `count` elements with the largest keys, in descending order of key.
+    ///
+    /// Only `count` elements are kept in memory, and it runs in `O(n log count)` time.
+    /// It's equivalent to `order_by_descending(f).take(count)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use linq::iter::Enumerable;
+    ///
+    /// let x = vec![5, 1, 9, 3, 7];
+    /// let e: Vec<i32> = x.into_iter().top_k(3, |p| *p).collect();
+    /// assert_eq!(e, vec![9, 7, 5]);
+    /// ```
+    fn top_k<TKey, F>(self, count: usize, f: F) -> OrderedIterator<Self::Item, CachedKey<F>>
+    where
+        Self: Sized,
+        TKey: Ord,
+        F: Fn(&Self::Item) -> TKey,
+    {
+        m_order_by::first_k(self, count, f, true)
+    }
+
+    /// Returns the `count` elements with the smallest keys, in ascending order of key.
+    ///
+    /// Only `count` elements are kept in memory, and it runs in `O(n log count)` time.
+    /// It's equivalent to `order_by(f).take(count)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use linq::iter::Enumerable;
+    ///
+    /// let x = vec![5, 1, 9, 3, 7];
+    /// let e: Vec<i32> = x.into_iter().bottom_k(3, |p| *p).collect();
+    /// assert_eq!(e, vec![1, 3, 5]);
+    /// ```
+    fn bottom_k<TKey, F>(self, count: usize, f: F) -> OrderedIterator<Self::Item, CachedKey<F>>
+    where
+        Self: Sized,
+        TKey: Ord,
+        F: Fn(&Self::Item) -> TKey,
+    {
+        m_order_by::first_k(self, count, f, false)
+    }
+
     /// Concatenates two sequences.
     ///
     /// # Examples
@@ -185,6 +524,109 @@ pub trait Enumerable: Iterator {
         m_builtin::concate(self, other)
     }
 
+    /// Concatenates a sequence with every sequence of a collection of sequences.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use linq::iter::Enumerable;
+    ///
+    /// let x = 0..10;
+    /// let y = vec![10..20, 20..30];
+    /// let e = x.concate_all(y);
+    /// assert!((0..30).eq(e));
+    ///
+    /// let e: Vec<i32> = vec![0].into_iter().concate_all(vec![vec![1], vec![2, 3]]).collect();
+    /// assert_eq!(e, vec![0, 1, 2, 3]);
+    /// ```
+    fn concate_all<U>(self, others: U) -> ConcateAllIterator<Self, U::IntoIter>
+    where
+        Self: Sized,
+        U: IntoIterator,
+        U::Item: IntoIterator<Item = Self::Item>,
+    {
+        m_builtin::concate_all(self, others)
+    }
+
+    /// Appends a value to the end of the sequence.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use linq::iter::Enumerable;
+    ///
+    /// let e: Vec<i32> = (0..3).append(10).collect();
+    /// assert_eq!(e, vec![0, 1, 2, 10]);
+    /// ```
+    fn append(self, item: Self::Item) -> AppendIterator<Self, Self::Item>
+    where
+        Self: Sized,
+    {
+        m_builtin::append(self, item)
+    }
+
+    /// Adds a value to the beginning of the sequence.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use linq::iter::Enumerable;
+    ///
+    /// let e: Vec<i32> = (0..3).prepend(10).collect();
+    /// assert_eq!(e, vec![10, 0, 1, 2]);
+    /// ```
+    fn prepend(self, item: Self::Item) -> PrependIterator<Self, Self::Item>
+    where
+        Self: Sized,
+    {
+        m_builtin::prepend(self, item)
+    }
+
+    /// Returns the elements of the sequence, or a singleton sequence containing the specified value if the sequence is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use linq::iter::Enumerable;
+    ///
+    /// let e: Vec<i32> = (0..0).default_if_empty(5).collect();
+    /// assert_eq!(e, vec![5]);
+    ///
+    /// let e: Vec<i32> = (0..2).default_if_empty(5).collect();
+    /// assert_eq!(e, vec![0, 1]);
+    /// ```
+    fn default_if_empty(self, value: Self::Item) -> DefaultIfEmptyIterator<Self>
+    where
+        Self: Sized,
+    {
+        m_builtin::default_if_empty(self, value)
+    }
+
+    /// Merges two sequences which are both sorted in ascending order of a key, without re-sorting them.
+    ///
+    /// Elements with equal keys are yielded from this sequence first. See `merge_sorted_all` to merge more
+    /// than two sequences.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use linq::iter::Enumerable;
+    ///
+    /// let x = vec![1, 3, 5];
+    /// let y = vec![2, 3, 4, 6];
+    /// let e: Vec<i32> = x.into_iter().merge_sorted(y.into_iter(), |p| *p).collect();
+    /// assert_eq!(e, vec![1, 2, 3, 3, 4, 5, 6]);
+    /// ```
+    fn merge_sorted<U, TKey, F>(self, other: U, key: F) -> MergeSortedIterator<Self, U, TKey, F>
+    where
+        Self: Sized,
+        U: Enumerable<Item = Self::Item>,
+        TKey: Ord,
+        F: Fn(&Self::Item) -> TKey,
+    {
+        m_merge::merge_sorted(self, other, key)
+    }
+
     /// Returns the first element of a sequence.
     ///
     /// # Examples
@@ -285,39 +727,927 @@ pub trait Enumerable: Iterator {
         m_method::contains(self, value)
     }
 
-    /// Applies an accumulator function over a sequence. The specified seed value is used as the initial accumulator value.
+    /// Determines whether two sequences are equal by comparing the elements by using the default equality comparer.
     ///
     /// # Examples
     ///
     /// ```
     /// use linq::iter::Enumerable;
     ///
-    /// let x = 0..10;
-    /// assert_eq!(x.clone().aggregate(1, |b, v| b * v), x.clone().product());
+    /// assert!((0..3).sequence_equal(vec![0, 1, 2]));
+    /// assert!(!(0..3).sequence_equal(0..4));
     /// ```
-    fn aggregate<B, F>(self, init: B, f: F) -> B
+    fn sequence_equal<U>(self, other: U) -> bool
     where
         Self: Sized,
-        F: FnMut(B, Self::Item) -> B,
+        Self::Item: PartialEq,
+        U: IntoIterator<Item = Self::Item>,
     {
-        m_builtin::aggregate(self, init, f)
+        m_method::sequence_equal_by(self, other, |a, b| a == b)
     }
 
-    fn distinct(self) -> m_distinct::DistinctIterator<Self>
-    where
-        Self: Sized,
-        Self::Item: Eq + std::hash::Hash + Copy,
+    /// Determines whether two sequences are equal by comparing their elements by using a specified comparer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use linq::iter::Enumerable;
+    ///
+    /// let x = vec!["a", "B", "c"];
+    /// assert!(x
+    ///     .into_iter()
+    ///     .sequence_equal_by(vec!["A", "b", "C"], |a, b| a.eq_ignore_ascii_case(b)));
+    /// ```
+    fn sequence_equal_by<U, F>(self, other: U, comparer: F) -> bool
+    where
+        Self: Sized,
+        U: IntoIterator,
+        F: FnMut(&Self::Item, &U::Item) -> bool,
+    {
+        m_method::sequence_equal_by(self, other, comparer)
+    }
+
+    /// Finds the first position where two sequences diverge.
+    ///
+    /// Returns `None` if the sequences are equal. Otherwise returns the index and
+    /// `EitherOrBoth::Both` with the two differing values, or `EitherOrBoth::Left` / `EitherOrBoth::Right`
+    /// with the remaining value if the other sequence ended early.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use linq::iter::{EitherOrBoth, Enumerable};
+    ///
+    /// assert_eq!((0..3).first_difference(0..3), None);
+    /// assert_eq!(
+    ///     (0..3).first_difference(vec![0, 5, 2]),
+    ///     Some((1, EitherOrBoth::Both(1, 5)))
+    /// );
+    /// assert_eq!(
+    ///     (0..2).first_difference(0..3),
+    ///     Some((2, EitherOrBoth::Right(2)))
+    /// );
+    /// ```
+    fn first_difference<U>(self, other: U) -> Option<SequenceDifference<Self::Item, Self::Item>>
+    where
+        Self: Sized,
+        Self::Item: PartialEq,
+        U: IntoIterator<Item = Self::Item>,
+    {
+        m_method::first_difference_by(self, other, |a, b| a == b)
+    }
+
+    /// Applies an accumulator function over a sequence. The specified seed value is used as the initial accumulator value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use linq::iter::Enumerable;
+    ///
+    /// let x = 0..10;
+    /// assert_eq!(x.clone().aggregate(1, |b, v| b * v), x.clone().product());
+    /// ```
+    fn aggregate<B, F>(self, init: B, f: F) -> B
+    where
+        Self: Sized,
+        F: FnMut(B, Self::Item) -> B,
+    {
+        m_builtin::aggregate(self, init, f)
+    }
+
+    /// Applies an accumulator function over a sequence, yielding the accumulator value after each element.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use linq::iter::Enumerable;
+    ///
+    /// let e: Vec<i32> = (1..5).scan_aggregate(0, |acc, v| acc + v).collect();
+    /// assert_eq!(e, vec![1, 3, 6, 10]);
+    /// ```
+    fn scan_aggregate<B, F>(self, seed: B, f: F) -> ScanAggregateIterator<Self, B, F>
+    where
+        Self: Sized,
+        B: Clone,
+        F: FnMut(&B, Self::Item) -> B,
+    {
+        m_scan::scan_aggregate(self, seed, f)
+    }
+
+    /// Returns each element paired with the element before it, starting from the second element.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use linq::iter::Enumerable;
+    ///
+    /// let e: Vec<i32> = vec![1, 4, 9, 16]
+    ///     .into_iter()
+    ///     .pairwise()
+    ///     .select(|(prev, cur)| cur - prev)
+    ///     .collect();
+    /// assert_eq!(e, vec![3, 5, 7]);
+    /// ```
+    fn pairwise(self) -> PairwiseIterator<Self>
+    where
+        Self: Sized,
+        Self::Item: Clone,
+    {
+        m_scan::pairwise(self)
+    }
+
+    /// Pairs each element with the element `n` positions earlier, or `None` if there is no such element.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use linq::iter::Enumerable;
+    ///
+    /// let e: Vec<_> = (1..5).lag(2).collect();
+    /// assert_eq!(e, vec![(1, None), (2, None), (3, Some(1)), (4, Some(2))]);
+    /// ```
+    fn lag(self, n: usize) -> LagIterator<Self>
+    where
+        Self: Sized,
+        Self::Item: Clone,
+    {
+        m_scan::lag(self, n)
+    }
+
+    /// Pairs each element with the element `n` positions later, or `None` if there is no such element.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use linq::iter::Enumerable;
+    ///
+    /// let e: Vec<_> = (1..5).lead(2).collect();
+    /// assert_eq!(e, vec![(1, Some(3)), (2, Some(4)), (3, None), (4, None)]);
+    /// ```
+    fn lead(self, n: usize) -> LeadIterator<Self>
+    where
+        Self: Sized,
+        Self::Item: Clone,
+    {
+        m_scan::lead(self, n)
+    }
+
+    /// Creates sliding windows of `size` consecutive elements, advancing by one element at a time.
+    ///
+    /// Each window is borrowed as a slice from a reused buffer. Use `next_window` to step through them,
+    /// or `select` to project each window into an iterator.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use linq::iter::Enumerable;
+    ///
+    /// let mut windows = (1..5).window(3);
+    /// assert_eq!(windows.next_window(), Some(&[1, 2, 3][..]));
+    /// assert_eq!(windows.next_window(), Some(&[2, 3, 4][..]));
+    /// assert_eq!(windows.next_window(), None);
+    ///
+    /// let e: Vec<i32> = (1..6).window(2).select(|w| w[1] * w[0]).collect();
+    /// assert_eq!(e, vec![2, 6, 12, 20]);
+    /// ```
+    fn window(self, size: usize) -> SlidingWindow<Self>
+    where
+        Self: Sized,
+        Self::Item: Clone,
+    {
+        m_sliding::window(self, size)
+    }
+
+    /// Computes the sum of each sliding window of `size` consecutive elements.
+    ///
+    /// The sum is updated incrementally, adding the entering element and subtracting the leaving one.
+    /// For floats, rounding errors accumulate over long sequences, and a large element which left the window
+    /// can swallow the smaller ones still in it. Use `window(size).select(|w| w.iter().sum())` to sum each
+    /// window from scratch instead.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use linq::iter::Enumerable;
+    ///
+    /// let e: Vec<i32> = (1..6).moving_sum(3).collect();
+    /// assert_eq!(e, vec![6, 9, 12]);
+    /// ```
+    fn moving_sum(self, size: usize) -> MovingSumIterator<Self>
+    where
+        Self: Sized,
+        Self::Item: Copy
+            + Default
+            + std::ops::Add<Output = Self::Item>
+            + std::ops::Sub<Output = Self::Item>,
+    {
+        m_sliding::moving_sum(self, size)
+    }
+
+    /// Computes the average of each sliding window of `size` consecutive elements.
+    ///
+    /// It's based on `moving_sum`, so floats have the same rounding errors.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use linq::iter::Enumerable;
+    ///
+    /// let e: Vec<f64> = vec![1.0, 2.0, 4.0, 8.0]
+    ///     .into_iter()
+    ///     .moving_average(2)
+    ///     .collect();
+    /// assert_eq!(e, vec![1.5, 3.0, 6.0]);
+    /// ```
+    fn moving_average<A>(self, size: usize) -> MovingAverageIterator<Self, A>
+    where
+        Self: Sized,
+        Self::Item: Copy
+            + Default
+            + std::ops::Add<Output = Self::Item>
+            + std::ops::Sub<Output = Self::Item>,
+        A: Average<Self::Item>,
+    {
+        m_sliding::moving_average(self, size)
+    }
+
+    /// Computes the minimum of each sliding window of `size` consecutive elements.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use linq::iter::Enumerable;
+    ///
+    /// let e: Vec<i32> = vec![4, 2, 5, 3, 1].into_iter().moving_min(2).collect();
+    /// assert_eq!(e, vec![2, 2, 3, 1]);
+    /// ```
+    fn moving_min(self, size: usize) -> MovingExtremumIterator<Self>
+    where
+        Self: Sized,
+        Self::Item: Ord + Clone,
+    {
+        m_sliding::moving_extremum(self, size, false)
+    }
+
+    /// Computes the maximum of each sliding window of `size` consecutive elements.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use linq::iter::Enumerable;
+    ///
+    /// let e: Vec<i32> = vec![4, 2, 5, 3, 1].into_iter().moving_max(2).collect();
+    /// assert_eq!(e, vec![4, 5, 5, 3]);
+    /// ```
+    fn moving_max(self, size: usize) -> MovingExtremumIterator<Self>
+    where
+        Self: Sized,
+        Self::Item: Ord + Clone,
+    {
+        m_sliding::moving_extremum(self, size, true)
+    }
+
+    /// Groups the elements of a sequence into partitions for window functions such as `row_number` and `rank`.
+    ///
+    /// The partitions are yielded in the order their first element appears, and the elements of each partition
+    /// are yielded in the order given by `order_by` or `order_by_descending`, paired with their window value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use linq::iter::Enumerable;
+    ///
+    /// let scores = vec![("a", 3), ("b", 5), ("a", 7), ("b", 5), ("a", 1)];
+    /// let e: Vec<_> = scores
+    ///     .into_iter()
+    ///     .over(|p| p.0)
+    ///     .order_by_descending(|p| p.1)
+    ///     .rank()
+    ///     .collect();
+    ///
+    /// assert_eq!(
+    ///     e,
+    ///     vec![
+    ///         (("a", 7), 1),
+    ///         (("a", 3), 2),
+    ///         (("a", 1), 3),
+    ///         (("b", 5), 1),
+    ///         (("b", 5), 1)
+    ///     ]
+    /// );
+    /// ```
+    fn over<TPartition, F>(self, partition: F) -> PartitionWindow<Self, F>
+    where
+        Self: Sized,
+        TPartition: Eq + std::hash::Hash,
+        F: Fn(&Self::Item) -> TPartition,
+    {
+        m_window::over(self, partition)
+    }
+
+    /// Estimates the number of distinct elements with a HyperLogLog sketch of `2^precision` registers.
+    ///
+    /// Unlike `distinct`, the memory used does not grow with the number of elements.
+    /// The relative error is about `1.04 / sqrt(2^precision)`.
+    /// Use `HyperLogLog` directly to merge the counts of several sequences.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `precision` is not between 4 and 16.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use linq::iter::Enumerable;
+    ///
+    /// let count = (0..100_000).select(|p| p % 5000).approx_count_distinct(12);
+    /// assert!((4800..5200).contains(&count));
+    /// ```
+    fn approx_count_distinct(self, precision: u8) -> u64
+    where
+        Self: Sized,
+        Self::Item: std::hash::Hash,
+    {
+        m_sketch::approx_count_distinct(self, precision)
+    }
+
+    /// Estimates the given quantiles (between 0 and 1) with a KLL sketch of accuracy `k`,
+    /// or returns `None` if the sequence is empty.
+    ///
+    /// Use `QuantileSketch` directly to merge the quantiles of several sequences.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `k` is less than 2 or a quantile is not between 0 and 1.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use linq::iter::Enumerable;
+    ///
+    /// let e = (0..10_000).approx_quantiles(200, &[0.0, 0.5, 1.0]).unwrap();
+    /// assert_eq!(e[0], 0);
+    /// assert!((4800..5200).contains(&e[1]));
+    /// assert_eq!(e[2], 9999);
+    /// ```
+    fn approx_quantiles(self, k: usize, quantiles: &[f64]) -> Option<Vec<Self::Item>>
+    where
+        Self: Sized,
+        Self::Item: Ord + Clone,
+    {
+        m_sketch::approx_quantiles(self, k, quantiles)
+    }
+
+    /// Selects `count` elements uniformly at random in a single pass, using reservoir sampling.
+    ///
+    /// The same seed always selects the same elements. If the sequence has fewer than `count` elements, all of
+    /// them are returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use linq::iter::Enumerable;
+    ///
+    /// let e: Vec<i32> = (0..100).sample(5, 42).collect();
+    /// assert_eq!(e.len(), 5);
+    /// assert_eq!(e, (0..100).sample(5, 42).collect::<Vec<_>>());
+    /// ```
+    fn sample(self, count: usize, seed: u64) -> SampleIterator<Self::Item>
+    where
+        Self: Sized,
+    {
+        m_random::sample(self, count, seed)
+    }
+
+    /// Returns the elements in a random order determined by the seed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use linq::iter::Enumerable;
+    ///
+    /// let mut e: Vec<i32> = (0..10).shuffle(7).collect();
+    /// assert_eq!(e, (0..10).shuffle(7).collect::<Vec<_>>());
+    ///
+    /// e.sort();
+    /// assert_eq!(e, (0..10).collect::<Vec<_>>());
+    /// ```
+    fn shuffle(self, seed: u64) -> ShuffleIterator<Self::Item>
+    where
+        Self: Sized,
+    {
+        m_random::shuffle(self, seed)
+    }
+
+    /// Returns an element chosen uniformly at random in a single pass, or `None` if the sequence is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use linq::iter::Enumerable;
+    ///
+    /// let e = (0..10).random_element(3).unwrap();
+    /// assert!((0..10).contains(&e));
+    /// assert_eq!((0..0).random_element(3), None);
+    /// ```
+    fn random_element(self, seed: u64) -> Option<Self::Item>
+    where
+        Self: Sized,
+    {
+        m_random::random_element(self, seed)
+    }
+
+    /// Returns every `k`-element combination of the elements, in lexicographic order of their positions.
+    ///
+    /// The sequence is buffered once, and each combination is cloned into a new `Vec`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use linq::iter::Enumerable;
+    ///
+    /// let e: Vec<_> = (1..5).combinations(2).collect();
+    /// assert_eq!(
+    ///     e,
+    ///     vec![
+    ///         vec![1, 2],
+    ///         vec![1, 3],
+    ///         vec![1, 4],
+    ///         vec![2, 3],
+    ///         vec![2, 4],
+    ///         vec![3, 4]
+    ///     ]
+    /// );
+    /// ```
+    fn combinations(self, k: usize) -> CombinationsIterator<Self::Item>
+    where
+        Self: Sized,
+        Self::Item: Clone,
+    {
+        m_combinatorics::combinations(self, k)
+    }
+
+    /// Returns every `k`-element combination of the elements where an element may be chosen more than once,
+    /// in lexicographic order of their positions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use linq::iter::Enumerable;
+    ///
+    /// let e: Vec<_> = (1..3).combinations_with_replacement(2).collect();
+    /// assert_eq!(e, vec![vec![1, 1], vec![1, 2], vec![2, 2]]);
+    /// ```
+    fn combinations_with_replacement(self, k: usize) -> CombinationsIterator<Self::Item>
+    where
+        Self: Sized,
+        Self::Item: Clone,
+    {
+        m_combinatorics::combinations_with_replacement(self, k)
+    }
+
+    /// Returns every ordered selection of `k` distinct elements, in lexicographic order of their positions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use linq::iter::Enumerable;
+    ///
+    /// let e: Vec<_> = (1..4).permutations(2).collect();
+    /// assert_eq!(
+    ///     e,
+    ///     vec![
+    ///         vec![1, 2],
+    ///         vec![1, 3],
+    ///         vec![2, 1],
+    ///         vec![2, 3],
+    ///         vec![3, 1],
+    ///         vec![3, 2]
+    ///     ]
+    /// );
+    /// ```
+    fn permutations(self, k: usize) -> PermutationsIterator<Self::Item>
+    where
+        Self: Sized,
+        Self::Item: Clone,
+    {
+        m_combinatorics::permutations(self, k)
+    }
+
+    /// Returns every subset of the elements, ordered by size and then lexicographically by position.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use linq::iter::Enumerable;
+    ///
+    /// let e: Vec<_> = (1..4).powerset().collect();
+    /// assert_eq!(
+    ///     e,
+    ///     vec![
+    ///         vec![],
+    ///         vec![1],
+    ///         vec![2],
+    ///         vec![3],
+    ///         vec![1, 2],
+    ///         vec![1, 3],
+    ///         vec![2, 3],
+    ///         vec![1, 2, 3]
+    ///     ]
+    /// );
+    /// ```
+    fn powerset(self) -> PowersetIterator<Self::Item>
+    where
+        Self: Sized,
+        Self::Item: Clone,
+    {
+        m_combinatorics::powerset(self)
+    }
+
+    /// Recursively expands each element into the children returned by `children`, visiting them in depth-first
+    /// pre-order.
+    ///
+    /// Call `with_depth` on the result to get each element paired with its depth, and `distinct_by` to visit
+    /// every id only once, which keeps cyclic graphs from being expanded forever.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use linq::iter::Enumerable;
+    ///
+    /// let e: Vec<i32> = vec![1].into_iter().traverse_depth_first(|p| {
+    ///     if *p < 4 { vec![p * 2, p * 2 + 1] } else { vec![] }
+    /// }).collect();
+    /// assert_eq!(e, vec![1, 2, 4, 5, 3, 6, 7]);
+    ///
+    /// let e: Vec<(usize, i32)> = (0..1)
+    ///     .traverse_depth_first(|p| vec![(p + 1) % 3])
+    ///     .distinct_by(|p| *p)
+    ///     .with_depth()
+    ///     .collect();
+    /// assert_eq!(e, vec![(0, 0), (1, 1), (2, 2)]);
+    /// ```
+    fn traverse_depth_first<TCollection, F>(
+        self,
+        children: F,
+    ) -> TraverseIterator<Self, F, TCollection>
+    where
+        Self: Sized,
+        TCollection: IntoIterator<Item = Self::Item>,
+        F: FnMut(&Self::Item) -> TCollection,
+    {
+        m_traverse::traverse(self, children, false)
+    }
+
+    /// Recursively expands each element into the children returned by `children`, visiting them level by level.
+    ///
+    /// Call `with_depth` on the result to get each element paired with its depth, and `distinct_by` to visit
+    /// every id only once, which keeps cyclic graphs from being expanded forever.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use linq::iter::Enumerable;
+    ///
+    /// let e: Vec<(usize, i32)> = vec![1].into_iter().traverse_breadth_first(|p| {
+    ///     if *p < 4 { vec![p * 2, p * 2 + 1] } else { vec![] }
+    /// }).with_depth().collect();
+    /// assert_eq!(e, vec![(0, 1), (1, 2), (1, 3), (2, 4), (2, 5), (2, 6), (2, 7)]);
+    /// ```
+    fn traverse_breadth_first<TCollection, F>(
+        self,
+        children: F,
+    ) -> TraverseIterator<Self, F, TCollection>
+    where
+        Self: Sized,
+        TCollection: IntoIterator<Item = Self::Item>,
+        F: FnMut(&Self::Item) -> TCollection,
+    {
+        m_traverse::traverse(self, children, true)
+    }
+
+    /// Builds a tree (or forest) from flat rows, where each row refers to its parent by id.
+    ///
+    /// Rows whose `parent_id` is `None` become roots. Fails if an id is duplicated, a parent id doesn't exist,
+    /// or the rows form a cycle.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use linq::iter::Enumerable;
+    ///
+    /// let rows = vec![(1, None), (2, Some(1)), (3, Some(2)), (4, Some(1))];
+    /// let tree = rows.into_iter().to_tree(|p| p.0, |p| p.1).unwrap();
+    ///
+    /// let root = tree.roots().next().unwrap();
+    /// let children: Vec<i32> = root.children().map(|p| p.value().0).collect();
+    /// assert_eq!(children, vec![2, 4]);
+    ///
+    /// let node = tree.node_by_id(&3).unwrap();
+    /// assert_eq!(node.depth(), 2);
+    /// let path: Vec<i32> = node.path_to_root().map(|p| p.value().0).collect();
+    /// assert_eq!(path, vec![3, 2, 1]);
+    ///
+    /// assert_eq!(root.subtree().count(), 4);
+    /// ```
+    fn to_tree<TKey, FI, FP>(
+        self,
+        id: FI,
+        parent_id: FP,
+    ) -> Result<Tree<Self::Item, TKey>, TreeError<TKey>>
+    where
+        Self: Sized,
+        TKey: Eq + std::hash::Hash + Clone,
+        FI: Fn(&Self::Item) -> TKey,
+        FP: Fn(&Self::Item) -> Option<TKey>,
+    {
+        m_tree::to_tree(self, id, parent_id)
+    }
+
+    /// Sorts the elements so that each one comes after all of its dependencies.
+    ///
+    /// Elements which don't depend on each other keep their source order.
+    /// Fails if an id is duplicated, a dependency doesn't exist, or the dependencies form a cycle.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use linq::iter::{Enumerable, TopologicalSortError};
+    ///
+    /// let steps = vec![("link", vec!["compile"]), ("compile", vec!["fetch"]), ("fetch", vec![]), ("lint", vec![])];
+    /// let e: Vec<&str> = steps
+    ///     .into_iter()
+    ///     .topological_sort(|p| p.0, |p| p.1.clone())
+    ///     .unwrap()
+    ///     .select(|p| p.0)
+    ///     .collect();
+    /// assert_eq!(e, vec!["fetch", "compile", "link", "lint"]);
+    ///
+    /// let steps = vec![("a", vec!["b"]), ("b", vec!["a"])];
+    /// let e = steps.into_iter().topological_sort(|p| p.0, |p| p.1.clone());
+    /// assert_eq!(e.unwrap_err(), TopologicalSortError::Cycle(vec!["a", "b"]));
+    /// ```
+    fn topological_sort<TKey, TCollection, FI, FD>(
+        self,
+        id: FI,
+        dependencies: FD,
+    ) -> Result<TopologicalSortIterator<Self::Item>, TopologicalSortError<TKey>>
+    where
+        Self: Sized,
+        TKey: Eq + std::hash::Hash + Clone,
+        TCollection: IntoIterator<Item = TKey>,
+        FI: Fn(&Self::Item) -> TKey,
+        FD: Fn(&Self::Item) -> TCollection,
+    {
+        m_topological::topological_sort(self, id, dependencies)
+    }
+
+    fn distinct(self) -> m_distinct::DistinctIterator<Self>
+    where
+        Self: Sized,
+        Self::Item: Eq + std::hash::Hash + Copy,
+    {
+        m_distinct::distinct(self)
+    }
+
+    /// Returns distinct floats from a sequence.
+    ///
+    /// Two floats are the same when they are equal in IEEE 754 `totalOrder`,
+    /// so `0.0` and `-0.0` are distinct, and NaNs with the same bits are the same.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use linq::iter::Enumerable;
+    ///
+    /// let x = vec![1.5, f64::NAN, 1.5, 2.0, f64::NAN];
+    /// let e: Vec<f64> = x.into_iter().distinct_float().collect();
+    /// assert_eq!(e.len(), 3);
+    /// assert!(e[1].is_nan());
+    /// ```
+    fn distinct_float(self) -> DistinctFloatIterator<Self>
+    where
+        Self: Sized,
+        Self::Item: Float,
+    {
+        m_float::distinct_float(self)
+    }
+
+    fn union<U>(self, union_with: U) -> m_union::UnionIterator<Self, U>
+    where
+        Self: Sized,
+        Self::Item: Eq + std::hash::Hash + Copy,
+        U: Enumerable<Item = Self::Item>,
+    {
+        m_union::union(self, union_with)
+    }
+
+    /// Returns distinct elements from a sequence sorted in ascending order of a key, keeping the first element
+    /// of each run of equal keys.
+    ///
+    /// Unlike `distinct`, it doesn't hash and uses constant memory. Call `validated` on the result to check that
+    /// the input really is sorted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use linq::iter::Enumerable;
+    ///
+    /// let e: Vec<i32> = vec![1, 1, 2, 3, 3, 3].into_iter().sorted_distinct(|p| *p).collect();
+    /// assert_eq!(e, vec![1, 2, 3]);
+    /// ```
+    fn sorted_distinct<TKey, F>(self, key: F) -> SortedDistinctIterator<Self, TKey, F>
+    where
+        Self: Sized,
+        TKey: Ord,
+        F: Fn(&Self::Item) -> TKey,
+    {
+        m_sorted::sorted_distinct(self, key)
+    }
+
+    /// Produces the set union of two sequences sorted in ascending order of a key, by merging them.
+    ///
+    /// Each key is yielded once, from this sequence if it appears in both. Call `validated` on the result to
+    /// check that the inputs really are sorted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use linq::iter::Enumerable;
+    ///
+    /// let e: Vec<i32> = vec![1, 3, 3, 5].into_iter().sorted_union(vec![2, 3, 6].into_iter(), |p| *p).collect();
+    /// assert_eq!(e, vec![1, 2, 3, 5, 6]);
+    /// ```
+    fn sorted_union<U, TKey, F>(self, other: U, key: F) -> SortedSetIterator<Self, U, TKey, F>
+    where
+        Self: Sized,
+        U: Enumerable<Item = Self::Item>,
+        TKey: Ord,
+        F: Fn(&Self::Item) -> TKey,
+    {
+        m_sorted::sorted_set(self, other, key, SetOperation::Union)
+    }
+
+    /// Produces the set intersection of two sequences sorted in ascending order of a key, by merging them.
+    ///
+    /// Each common key is yielded once, from this sequence. Call `validated` on the result to check that the
+    /// inputs really are sorted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use linq::iter::Enumerable;
+    ///
+    /// let e: Vec<i32> = vec![1, 3, 3, 5].into_iter().sorted_intersect(vec![2, 3, 5].into_iter(), |p| *p).collect();
+    /// assert_eq!(e, vec![3, 5]);
+    /// ```
+    fn sorted_intersect<U, TKey, F>(self, other: U, key: F) -> SortedSetIterator<Self, U, TKey, F>
+    where
+        Self: Sized,
+        U: Enumerable<Item = Self::Item>,
+        TKey: Ord,
+        F: Fn(&Self::Item) -> TKey,
+    {
+        m_sorted::sorted_set(self, other, key, SetOperation::Intersect)
+    }
+
+    /// Produces the set difference of two sequences sorted in ascending order of a key, by merging them.
+    ///
+    /// Each key of this sequence which is not in the other one is yielded once. Call `validated` on the result
+    /// to check that the inputs really are sorted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use linq::iter::Enumerable;
+    ///
+    /// let e: Vec<i32> = vec![1, 3, 3, 5].into_iter().sorted_except(vec![2, 3].into_iter(), |p| *p).collect();
+    /// assert_eq!(e, vec![1, 5]);
+    /// ```
+    fn sorted_except<U, TKey, F>(self, other: U, key: F) -> SortedSetIterator<Self, U, TKey, F>
+    where
+        Self: Sized,
+        U: Enumerable<Item = Self::Item>,
+        TKey: Ord,
+        F: Fn(&Self::Item) -> TKey,
     {
-        m_distinct::distinct(self)
+        m_sorted::sorted_set(self, other, key, SetOperation::Except)
     }
 
-    fn union<U>(self, union_with: U) -> m_union::UnionIterator<Self, U>
+    /// Correlates the elements of two sequences sorted in ascending order of their keys, by merging them.
+    ///
+    /// Every pair of elements with equal keys is passed to `result`. Only the elements of the other sequence
+    /// sharing the current key are buffered. Call `validated` on the result to check that the inputs really
+    /// are sorted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use linq::iter::Enumerable;
+    ///
+    /// let users = vec![(1, "ann"), (2, "bob"), (4, "eve")];
+    /// let orders = vec![(1, "tea"), (1, "cake"), (3, "pie"), (4, "jam")];
+    /// let e: Vec<_> = users
+    ///     .iter()
+    ///     .merge_join(orders.iter(), |u| u.0, |o| o.0, |u, o| (u.1, o.1))
+    ///     .collect();
+    /// assert_eq!(e, vec![("ann", "tea"), ("ann", "cake"), ("eve", "jam")]);
+    /// ```
+    fn merge_join<U, TKey, TResult, FI, FU, FR>(
+        self,
+        other: U,
+        key: FI,
+        other_key: FU,
+        result: FR,
+    ) -> MergeJoinIterator<Self, U, TKey, FI, FU, FR>
     where
         Self: Sized,
-        Self::Item: Eq + std::hash::Hash + Copy,
-        U: Enumerable<Item = Self::Item>,
+        U: Enumerable,
+        TKey: Ord,
+        FI: Fn(&Self::Item) -> TKey,
+        FU: Fn(&U::Item) -> TKey,
+        FR: FnMut(&Self::Item, &U::Item) -> TResult,
     {
-        m_union::union(self, union_with)
+        m_sorted::merge_join(self, other, key, other_key, result)
+    }
+
+    /// Correlates the elements of two sequences based on matching keys, spilling to temporary files
+    /// when the second sequence doesn't fit in memory.
+    ///
+    /// The second sequence is loaded into a hash table while its elements encoded by `Spill` stay within
+    /// `memory_budget` bytes, and then the first sequence is streamed against it. Otherwise both sequences
+    /// are partitioned by key hash into files in `temp_dir`, and the partitions are joined one by one,
+    /// partitioning them again while they're still over the budget. The files are removed when the returned
+    /// iterator is dropped.
+    ///
+    /// Every pair of elements with equal keys is passed to `result`. Without spilling, the pairs follow the order
+    /// of the first sequence, then of the second one; after spilling, they're grouped by partition.
+    /// Reading a partition can fail, so the iterator yields `io::Result`, and it stops after the first error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use linq::iter::Enumerable;
+    ///
+    /// let users = vec![1u32, 2, 4];
+    /// let orders = vec![10u32, 11, 30, 40];
+    /// let e: Vec<(u32, u32)> = users
+    ///     .into_iter()
+    ///     .grace_join(orders.into_iter(), |u| *u, |o| o / 10, |u, o| (*u, *o), 1 << 20, std::env::temp_dir())
+    ///     .unwrap()
+    ///     .collect::<std::io::Result<_>>()
+    ///     .unwrap();
+    /// assert_eq!(e, vec![(1, 10), (1, 11), (4, 40)]);
+    /// ```
+    fn grace_join<U, TKey, TResult, FI, FU, FR, P>(
+        self,
+        other: U,
+        key: FI,
+        other_key: FU,
+        result: FR,
+        memory_budget: usize,
+        temp_dir: P,
+    ) -> std::io::Result<GraceJoinIterator<Self, U::Item, TKey, FI, FU, FR>>
+    where
+        Self: Sized,
+        Self::Item: Spill,
+        U: Enumerable,
+        U::Item: Spill,
+        TKey: Eq + std::hash::Hash,
+        FI: Fn(&Self::Item) -> TKey,
+        FU: Fn(&U::Item) -> TKey,
+        FR: FnMut(&Self::Item, &U::Item) -> TResult,
+        P: AsRef<std::path::Path>,
+    {
+        m_join::grace_join(
+            self,
+            other,
+            key,
+            other_key,
+            result,
+            memory_budget,
+            temp_dir.as_ref(),
+        )
     }
 
     fn average<A>(self) -> A
@@ -327,6 +1657,395 @@ pub trait Enumerable: Iterator {
     {
         Average::average(self)
     }
+
+    /// Computes the median of the values selected from each element, or `None` if the sequence is empty.
+    ///
+    /// NaN values are skipped, so the result is `None` if nothing else remains.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use linq::iter::Enumerable;
+    ///
+    /// assert_eq!(vec![3, 1, 2].into_iter().median(|p| *p as f64), Some(2.0));
+    /// assert_eq!((1..5).median(|p| *p as f64), Some(2.5));
+    /// assert_eq!((0..0).median(|p| *p as f64), None);
+    /// ```
+    fn median<F>(self, f: F) -> Option<f64>
+    where
+        Self: Sized,
+        F: Fn(&Self::Item) -> f64,
+    {
+        m_statistics::percentile(self, 50.0, Interpolation::Linear, f)
+    }
+
+    /// Computes the `p`-th percentile (between 0 and 100) of the values selected from each element,
+    /// or `None` if the sequence is empty.
+    ///
+    /// NaN values are skipped, so the result is `None` if nothing else remains.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `p` is not between 0 and 100.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use linq::iter::{Enumerable, Interpolation};
+    ///
+    /// let x = 1..5;
+    /// assert_eq!(x.clone().percentile(50.0, Interpolation::Linear, |p| *p as f64), Some(2.5));
+    /// assert_eq!(x.clone().percentile(50.0, Interpolation::Lower, |p| *p as f64), Some(2.0));
+    /// assert_eq!(x.clone().percentile(100.0, Interpolation::Linear, |p| *p as f64), Some(4.0));
+    /// ```
+    fn percentile<F>(self, p: f64, interpolation: Interpolation, f: F) -> Option<f64>
+    where
+        Self: Sized,
+        F: Fn(&Self::Item) -> f64,
+    {
+        m_statistics::percentile(self, p, interpolation, f)
+    }
+
+    /// Returns the most frequent key selected from the elements, or `None` if the sequence is empty.
+    ///
+    /// Ties are broken in favour of the key that appears first.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use linq::iter::Enumerable;
+    ///
+    /// let x = vec!["a", "bb", "cc", "d", "ee"];
+    /// assert_eq!(x.iter().mode(|p| p.len()), Some(2));
+    /// ```
+    fn mode<TKey, F>(self, f: F) -> Option<TKey>
+    where
+        Self: Sized,
+        TKey: Eq + std::hash::Hash,
+        F: Fn(&Self::Item) -> TKey,
+    {
+        m_statistics::mode(self, f)
+    }
+
+    /// Computes the variance of the values selected from each element in a single pass.
+    ///
+    /// Returns `None` if the sequence is empty, or has a single element for a sample variance.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use linq::iter::{Enumerable, VarianceKind};
+    ///
+    /// let x = vec![2, 4, 4, 4, 5, 5, 7, 9];
+    /// assert_eq!(x.iter().variance(VarianceKind::Population, |p| **p as f64), Some(4.0));
+    /// assert_eq!(x.iter().variance(VarianceKind::Sample, |p| **p as f64), Some(32.0 / 7.0));
+    /// ```
+    fn variance<F>(self, kind: VarianceKind, f: F) -> Option<f64>
+    where
+        Self: Sized,
+        F: Fn(&Self::Item) -> f64,
+    {
+        m_statistics::variance(self, kind, f)
+    }
+
+    /// Computes the standard deviation of the values selected from each element in a single pass.
+    ///
+    /// Returns `None` if the sequence is empty, or has a single element for a sample standard deviation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use linq::iter::{Enumerable, VarianceKind};
+    ///
+    /// let x = vec![2, 4, 4, 4, 5, 5, 7, 9];
+    /// assert_eq!(x.iter().std_dev(VarianceKind::Population, |p| **p as f64), Some(2.0));
+    /// ```
+    fn std_dev<F>(self, kind: VarianceKind, f: F) -> Option<f64>
+    where
+        Self: Sized,
+        F: Fn(&Self::Item) -> f64,
+    {
+        m_statistics::variance(self, kind, f).map(f64::sqrt)
+    }
+
+    /// Computes the covariance of two values selected from each element in a single pass.
+    ///
+    /// Returns `None` if the sequence is empty, or has a single element for a sample covariance.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use linq::iter::{Enumerable, VarianceKind};
+    ///
+    /// let x = vec![(1, 2), (2, 4), (3, 6)];
+    /// let e = x.iter().covariance(VarianceKind::Sample, |p| p.0 as f64, |p| p.1 as f64);
+    /// assert_eq!(e, Some(2.0));
+    /// ```
+    fn covariance<FX, FY>(self, kind: VarianceKind, x: FX, y: FY) -> Option<f64>
+    where
+        Self: Sized,
+        FX: Fn(&Self::Item) -> f64,
+        FY: Fn(&Self::Item) -> f64,
+    {
+        m_statistics::covariance(self, kind, x, y)
+    }
+
+    /// Computes the Pearson correlation coefficient of two values selected from each element in a single pass.
+    ///
+    /// Returns `None` if there are fewer than two elements or either value is constant.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use linq::iter::Enumerable;
+    ///
+    /// let x = vec![(1, 5), (2, 3), (3, 1)];
+    /// assert_eq!(x.iter().correlation(|p| p.0 as f64, |p| p.1 as f64), Some(-1.0));
+    /// ```
+    fn correlation<FX, FY>(self, x: FX, y: FY) -> Option<f64>
+    where
+        Self: Sized,
+        FX: Fn(&Self::Item) -> f64,
+        FY: Fn(&Self::Item) -> f64,
+    {
+        m_statistics::correlation(self, x, y)
+    }
+
+    /// Computes the Spearman rank correlation coefficient of two keys selected from each element.
+    ///
+    /// Tied keys share the average of their ranks.
+    /// Returns `None` if there are fewer than two elements or either key is constant.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use linq::iter::Enumerable;
+    ///
+    /// let x = vec![(1, 1), (2, 8), (3, 27), (4, 64)];
+    /// assert_eq!(x.iter().spearman_correlation(|p| p.0, |p| p.1), Some(1.0));
+    /// ```
+    fn spearman_correlation<KX, KY, FX, FY>(self, x: FX, y: FY) -> Option<f64>
+    where
+        Self: Sized,
+        KX: Ord,
+        KY: Ord,
+        FX: Fn(&Self::Item) -> KX,
+        FY: Fn(&Self::Item) -> KY,
+    {
+        m_statistics::spearman_correlation(self, x, y)
+    }
+
+    /// Fits a least-squares line to two values selected from each element in a single pass.
+    ///
+    /// Returns `None` if there are fewer than two elements or the `x` value is constant.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use linq::iter::Enumerable;
+    ///
+    /// let x = vec![(0, 1), (1, 3), (2, 5)];
+    /// let e = x.iter().linear_regression(|p| p.0 as f64, |p| p.1 as f64).unwrap();
+    /// assert_eq!((e.slope, e.intercept, e.r_squared), (2.0, 1.0, 1.0));
+    /// ```
+    fn linear_regression<FX, FY>(self, x: FX, y: FY) -> Option<LinearRegression>
+    where
+        Self: Sized,
+        FX: Fn(&Self::Item) -> f64,
+        FY: Fn(&Self::Item) -> f64,
+    {
+        m_statistics::linear_regression(self, x, y)
+    }
+
+    /// Counts the values selected from each element into the bins described by `binning`.
+    ///
+    /// Every bin covers `start..end`, except the last one, which also includes its end.
+    /// `NaN` values are not counted. Infinite values are only counted by `Binning::Edges`,
+    /// since the other binnings span the finite values.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of bins is 0, or if the edges are fewer than two or not ascending.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use linq::iter::{Bin, Binning, Enumerable};
+    ///
+    /// let e = (0..10).histogram(Binning::EqualWidth(3), |p| *p as f64);
+    /// assert_eq!(
+    ///     e,
+    ///     vec![
+    ///         Bin { start: 0.0, end: 3.0, count: 3 },
+    ///         Bin { start: 3.0, end: 6.0, count: 3 },
+    ///         Bin { start: 6.0, end: 9.0, count: 4 }
+    ///     ]
+    /// );
+    /// ```
+    fn histogram<F>(self, binning: Binning, f: F) -> Vec<Bin>
+    where
+        Self: Sized,
+        F: Fn(&Self::Item) -> f64,
+    {
+        m_histogram::histogram(self, binning, f)
+    }
+
+    /// Groups the elements into the bins between each pair of consecutive `edges` according to a key.
+    ///
+    /// Every bucket covers `start..end`, except the last one, which also includes its end.
+    /// Elements whose key is outside of the edges or `NaN` are dropped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the edges are fewer than two or not ascending.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use linq::iter::Enumerable;
+    ///
+    /// let e = (0..10).bucket_by(&[0.0, 5.0, 8.0], |p| *p as f64);
+    /// assert_eq!(e[0].items, vec![0, 1, 2, 3, 4]);
+    /// assert_eq!(e[1].items, vec![5, 6, 7, 8]);
+    /// ```
+    fn bucket_by<F>(self, edges: &[f64], f: F) -> Vec<Bucket<Self::Item>>
+    where
+        Self: Sized,
+        F: Fn(&Self::Item) -> f64,
+    {
+        m_histogram::bucket_by(self, edges, f)
+    }
+
+    /// Returns the elements with the minimum and maximum keys in a single pass, or `None` if the sequence is empty.
+    ///
+    /// If several elements are equally minimum, the first one is returned.
+    /// If several elements are equally maximum, the last one is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use linq::iter::Enumerable;
+    ///
+    /// let x = vec!["bb", "a", "ccc", "d"];
+    /// assert_eq!(x.into_iter().min_max(|p| p.len()), Some(("a", "ccc")));
+    /// ```
+    fn min_max<TKey, F>(self, f: F) -> Option<(Self::Item, Self::Item)>
+    where
+        Self: Sized,
+        Self::Item: Clone,
+        TKey: Ord,
+        F: Fn(&Self::Item) -> TKey,
+    {
+        m_statistics::min_max(self, f)
+    }
+
+    /// Returns the minimum float of a sequence, skipping NaNs like `f64::min`.
+    ///
+    /// Other floats are compared by IEEE 754 `totalOrder`, so `-0.0` is less than `0.0`.
+    /// It returns `None` if there are no floats other than NaNs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use linq::iter::Enumerable;
+    ///
+    /// let x = vec![2.5, f64::NAN, -1.0, 0.5];
+    /// assert_eq!(x.into_iter().min_float(), Some(-1.0));
+    /// assert_eq!(vec![f64::NAN].into_iter().min_float(), None);
+    /// ```
+    fn min_float(self) -> Option<Self::Item>
+    where
+        Self: Sized,
+        Self::Item: Float,
+    {
+        m_float::extremum_float(self, std::cmp::Ordering::Less)
+    }
+
+    /// Returns the maximum float of a sequence, skipping NaNs like `f64::max`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use linq::iter::Enumerable;
+    ///
+    /// let x = vec![2.5f32, f32::NAN, -1.0, 0.5];
+    /// assert_eq!(x.into_iter().max_float(), Some(2.5));
+    /// ```
+    fn max_float(self) -> Option<Self::Item>
+    where
+        Self: Sized,
+        Self::Item: Float,
+    {
+        m_float::extremum_float(self, std::cmp::Ordering::Greater)
+    }
+
+    /// Applies a specified function to the corresponding elements of two sequences, producing a sequence of the results.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use linq::iter::Enumerable;
+    ///
+    /// let x = 1..4;
+    /// let y = vec![10, 20, 30, 40];
+    /// let e: Vec<i32> = x.zip_with(y.into_iter(), |a, b| a + b).collect();
+    ///
+    /// assert_eq!(e, vec![11, 22, 33]);
+    /// ```
+    fn zip_with<U, TResult, F>(self, other: U, f: F) -> ZipWithIterator<Self, U::IntoIter, F>
+    where
+        Self: Sized,
+        U: IntoIterator,
+        F: FnMut(Self::Item, U::Item) -> TResult,
+    {
+        m_zip::zip_with(self, other, f)
+    }
+
+    /// Produces a sequence of tuples with elements from three sequences, stopping at the shortest one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use linq::iter::Enumerable;
+    ///
+    /// let e: Vec<_> = (0..3).zip3("ab".chars(), vec![true; 5].into_iter()).collect();
+    ///
+    /// assert_eq!(e, vec![(0, 'a', true), (1, 'b', true)]);
+    /// ```
+    fn zip3<U, V>(self, second: U, third: V) -> Zip3Iterator<Self, U::IntoIter, V::IntoIter>
+    where
+        Self: Sized,
+        U: IntoIterator,
+        V: IntoIterator,
+    {
+        m_zip::zip3(self, second, third)
+    }
+
+    /// Iterates two sequences in lockstep until both are exhausted, yielding an `EitherOrBoth` for each position.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use linq::iter::{EitherOrBoth, Enumerable};
+    ///
+    /// let e: Vec<_> = (1..4).zip_longest(5..6).collect();
+    ///
+    /// assert_eq!(
+    ///     e,
+    ///     vec![
+    ///         EitherOrBoth::Both(1, 5),
+    ///         EitherOrBoth::Left(2),
+    ///         EitherOrBoth::Left(3)
+    ///     ]
+    /// );
+    /// ```
+    fn zip_longest<U>(self, other: U) -> ZipLongestIterator<Self, U::IntoIter>
+    where
+        Self: Sized,
+        U: IntoIterator,
+    {
+        m_zip::zip_longest(self, other)
+    }
 }
 
 impl<I, T> Enumerable for I where I: Iterator<Item = T> {}
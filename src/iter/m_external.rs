@@ -0,0 +1,220 @@
+use super::spill::{Spill, SpillFile, SpillReader};
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::io;
+use std::path::Path;
+
+/// The most runs which are merged at once, so that the count of open files stays bounded.
+const MAX_OPEN_RUNS: usize = 64;
+
+/// The fewest elements in a spilled run, so that a tiny budget doesn't create a file per element.
+const MIN_RUN_LEN: usize = 16;
+
+enum Run {
+    Memory(std::vec::IntoIter<Vec<u8>>),
+    File(SpillReader),
+}
+
+impl Run {
+    fn read<T: Spill>(&mut self) -> io::Result<Option<T>> {
+        match self {
+            Run::Memory(records) => records.next().map(|bytes| T::decode(&bytes)).transpose(),
+            Run::File(reader) => reader.read(),
+        }
+    }
+}
+
+/// The next element of a run. The heap yields the first key, and the earliest run among equal keys;
+/// descending order is the exact reverse.
+struct Head<K, T> {
+    key: K,
+    run: usize,
+    item: T,
+    descending: bool,
+}
+
+impl<K: Ord, T> Ord for Head<K, T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let order = other
+            .key
+            .cmp(&self.key)
+            .then_with(|| other.run.cmp(&self.run));
+        if self.descending {
+            order.reverse()
+        } else {
+            order
+        }
+    }
+}
+
+impl<K: Ord, T> PartialOrd for Head<K, T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<K: Ord, T> PartialEq for Head<K, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<K: Ord, T> Eq for Head<K, T> {}
+
+/// Merges sorted runs lazily. The spilled files are removed when it's dropped.
+pub struct ExternalOrderedIterator<T, K, F> {
+    // Fields are dropped in declaration order, so the readers are closed before their files are removed.
+    runs: Vec<Run>,
+    _files: Vec<SpillFile>,
+    heap: BinaryHeap<Head<K, T>>,
+    key: F,
+    descending: bool,
+    error: Option<io::Error>,
+}
+
+impl<T, K, F> ExternalOrderedIterator<T, K, F>
+where
+    T: Spill,
+    K: Ord,
+    F: Fn(&T) -> K,
+{
+    fn new(
+        files: Vec<SpillFile>,
+        memory: Vec<Vec<u8>>,
+        key: F,
+        descending: bool,
+    ) -> io::Result<Self> {
+        let mut runs = files
+            .iter()
+            .map(|file| file.open().map(Run::File))
+            .collect::<io::Result<Vec<_>>>()?;
+        // The elements in memory are the last ones of the source, so they're the last run.
+        runs.push(Run::Memory(memory.into_iter()));
+        let mut iter = ExternalOrderedIterator {
+            _files: files,
+            heap: BinaryHeap::with_capacity(runs.len()),
+            runs,
+            key,
+            descending,
+            error: None,
+        };
+        for run in 0..iter.runs.len() {
+            iter.advance(run)?;
+        }
+        Ok(iter)
+    }
+
+    fn advance(&mut self, run: usize) -> io::Result<()> {
+        if let Some(item) = self.runs[run].read::<T>()? {
+            self.heap.push(Head {
+                key: (self.key)(&item),
+                run,
+                item,
+                descending: self.descending,
+            });
+        }
+        Ok(())
+    }
+}
+
+impl<T, K, F> Iterator for ExternalOrderedIterator<T, K, F>
+where
+    T: Spill,
+    K: Ord,
+    F: Fn(&T) -> K,
+{
+    type Item = io::Result<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(e) = self.error.take() {
+            self.heap.clear();
+            return Some(Err(e));
+        }
+        let head = self.heap.pop()?;
+        if let Err(e) = self.advance(head.run) {
+            self.error = Some(e);
+        }
+        Some(Ok(head.item))
+    }
+}
+
+impl<T, K, F> std::iter::FusedIterator for ExternalOrderedIterator<T, K, F>
+where
+    T: Spill,
+    K: Ord,
+    F: Fn(&T) -> K,
+{
+}
+
+fn spill_run<K: Ord>(
+    buffer: &mut Vec<(K, Vec<u8>)>,
+    descending: bool,
+    temp_dir: &Path,
+) -> io::Result<SpillFile> {
+    sort_run(buffer, descending);
+    let (file, mut writer) = SpillFile::create(temp_dir)?;
+    for (_, bytes) in buffer.drain(..) {
+        writer.write_encoded(&bytes)?;
+    }
+    writer.finish()?;
+    Ok(file)
+}
+
+fn sort_run<K: Ord>(buffer: &mut [(K, Vec<u8>)], descending: bool) {
+    buffer.sort_by(|a, b| a.0.cmp(&b.0));
+    if descending {
+        buffer.reverse();
+    }
+}
+
+pub fn order_by_external<I, K, F>(
+    iter: I,
+    func: F,
+    memory_budget: usize,
+    temp_dir: &Path,
+    descending: bool,
+) -> io::Result<ExternalOrderedIterator<I::Item, K, F>>
+where
+    I: Iterator,
+    I::Item: Spill,
+    K: Ord,
+    F: Fn(&I::Item) -> K,
+{
+    let mut buffer = Vec::new();
+    let mut buffered = 0;
+    let mut files = Vec::new();
+    for item in iter {
+        let mut bytes = Vec::new();
+        item.encode(&mut bytes);
+        // Heap memory owned by the keys isn't known, so only their inline size is counted.
+        buffered += bytes.len() + std::mem::size_of::<(K, Vec<u8>)>();
+        buffer.push((func(&item), bytes));
+        if buffered >= memory_budget && buffer.len() >= MIN_RUN_LEN {
+            files.push(spill_run(&mut buffer, descending, temp_dir)?);
+            buffered = 0;
+        }
+    }
+    sort_run(&mut buffer, descending);
+    let memory = buffer.into_iter().map(|(_, bytes)| bytes).collect();
+
+    // Merge groups of adjacent runs until they can be opened at once. Adjacent runs keep the source order.
+    while files.len() > MAX_OPEN_RUNS {
+        let mut groups = files.into_iter();
+        files = Vec::new();
+        loop {
+            let mut group: Vec<_> = groups.by_ref().take(MAX_OPEN_RUNS).collect();
+            if group.len() <= 1 {
+                files.append(&mut group);
+                break;
+            }
+            let (file, mut writer) = SpillFile::create(temp_dir)?;
+            for item in ExternalOrderedIterator::new(group, Vec::new(), &func, descending)? {
+                writer.write(&item?)?;
+            }
+            writer.finish()?;
+            files.push(file);
+        }
+    }
+
+    ExternalOrderedIterator::new(files, memory, func, descending)
+}
@@ -0,0 +1,192 @@
+use super::m_order_by::{self, Entry, OrderedIterator, SortKey};
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::hash::{Hash, Hasher};
+
+/// A floating-point type which can be ordered by IEEE 754 `totalOrder`. It's implemented for `f32` and `f64`.
+pub trait Float: Copy {
+    fn total_cmp(&self, other: &Self) -> Ordering;
+
+    fn is_nan(self) -> bool;
+
+    /// Returns a NaN with the sign bit set when `negative` is `true`.
+    fn nan(negative: bool) -> Self;
+
+    fn to_bits(self) -> u64;
+}
+
+macro_rules! float {
+    ($($t:ty),*) => {
+        $(
+            impl Float for $t {
+                fn total_cmp(&self, other: &Self) -> Ordering {
+                    <$t>::total_cmp(self, other)
+                }
+
+                fn is_nan(self) -> bool {
+                    <$t>::is_nan(self)
+                }
+
+                fn nan(negative: bool) -> Self {
+                    if negative {
+                        -<$t>::NAN.abs()
+                    } else {
+                        <$t>::NAN.abs()
+                    }
+                }
+
+                fn to_bits(self) -> u64 {
+                    <$t>::to_bits(self) as u64
+                }
+            }
+        )*
+    };
+}
+
+float!(f32, f64);
+
+/// Where NaNs are placed by `TotalFloat::with_nan`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum NanPlacement {
+    /// NaNs are less than every other value.
+    First,
+    /// NaNs are greater than every other value.
+    Last,
+}
+
+/// A float key with a total ordering, so it implements `Ord`, `Eq` and `Hash`.
+///
+/// It follows IEEE 754 `totalOrder`, like `f64::total_cmp`:
+/// negative NaNs, negative infinity, negative numbers, `-0.0`, `+0.0`, positive numbers, positive infinity, positive NaNs.
+/// Two keys are equal only if their bits are equal.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TotalFloat<F>(pub F);
+
+impl<F: Float> TotalFloat<F> {
+    /// Creates a key which places every NaN at one end, whatever its sign.
+    ///
+    /// All NaNs become equal to each other.
+    pub fn with_nan(value: F, placement: NanPlacement) -> Self {
+        if value.is_nan() {
+            TotalFloat(F::nan(placement == NanPlacement::First))
+        } else {
+            TotalFloat(value)
+        }
+    }
+
+    /// Returns the wrapped value.
+    pub fn get(self) -> F {
+        self.0
+    }
+}
+
+impl<F: Float> PartialEq for TotalFloat<F> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<F: Float> Eq for TotalFloat<F> {}
+
+impl<F: Float> PartialOrd for TotalFloat<F> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<F: Float> Ord for TotalFloat<F> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+impl<F: Float> Hash for TotalFloat<F> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.to_bits().hash(state);
+    }
+}
+
+/// Wraps the float keys in `TotalFloat` once per element.
+#[derive(Clone)]
+pub struct FloatKey<F>(F);
+
+impl<T, K: Float, F: Fn(&T) -> K> SortKey<T> for FloatKey<F> {
+    type Key = TotalFloat<K>;
+
+    fn key(&self, item: &T) -> TotalFloat<K> {
+        TotalFloat((self.0)(item))
+    }
+
+    fn compare(&self, a: &Entry<TotalFloat<K>, T>, b: &Entry<TotalFloat<K>, T>) -> Ordering {
+        a.key.cmp(&b.key)
+    }
+}
+
+pub fn order_by_float<I: Iterator, K: Float, F>(
+    iter: I,
+    func: F,
+    descending: bool,
+) -> OrderedIterator<I::Item, FloatKey<F>>
+where
+    F: Fn(&I::Item) -> K,
+{
+    m_order_by::order_by_key(iter, FloatKey(func), descending)
+}
+
+#[derive(Clone)]
+pub struct DistinctFloatIterator<I>
+where
+    I: Iterator,
+    I::Item: Float,
+{
+    source: I,
+    seen: HashSet<TotalFloat<I::Item>>,
+}
+
+impl<I> Iterator for DistinctFloatIterator<I>
+where
+    I: Iterator,
+    I::Item: Float,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let seen = &mut self.seen;
+        self.source.find(|item| seen.insert(TotalFloat(*item)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.source.size_hint().1)
+    }
+}
+
+impl<I> std::iter::FusedIterator for DistinctFloatIterator<I>
+where
+    I: std::iter::FusedIterator,
+    I::Item: Float,
+{
+}
+
+pub fn distinct_float<I>(iter: I) -> DistinctFloatIterator<I>
+where
+    I: Iterator,
+    I::Item: Float,
+{
+    DistinctFloatIterator {
+        source: iter,
+        seen: HashSet::new(),
+    }
+}
+
+/// Finds the first extremum in total order, skipping NaNs.
+pub fn extremum_float<I>(iter: I, wanted: Ordering) -> Option<I::Item>
+where
+    I: Iterator,
+    I::Item: Float,
+{
+    iter.filter(|item| !item.is_nan())
+        .fold(None, |best, item| match best {
+            Some(best) if item.total_cmp(&best) != wanted => Some(best),
+            _ => Some(item),
+        })
+}
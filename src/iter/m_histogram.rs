@@ -0,0 +1,143 @@
+use super::m_statistics::{percentile_of_sorted, Interpolation};
+
+/// How the range of values is divided into the bins of a histogram.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Binning {
+    /// The given number of bins of equal width, spanning the minimum to the maximum finite value.
+    /// If every value is the same, there is a single bin holding all of them.
+    EqualWidth(usize),
+    /// Bins between each pair of consecutive edges, which must be ascending.
+    /// Values outside of the first and last edge are not counted.
+    Edges(Vec<f64>),
+    /// The given number of bins holding roughly the same number of finite values.
+    /// If every value is the same, there is a single bin holding all of them.
+    Quantile(usize),
+}
+
+/// A bin of a histogram, covering `start..end` (or `start..=end` for the last bin).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Bin {
+    pub start: f64,
+    pub end: f64,
+    pub count: usize,
+}
+
+/// The elements whose key falls into `start..end` (or `start..=end` for the last bucket).
+#[derive(Clone, Debug, PartialEq)]
+pub struct Bucket<T> {
+    pub start: f64,
+    pub end: f64,
+    pub items: Vec<T>,
+}
+
+pub fn histogram<I, F>(iter: I, binning: Binning, f: F) -> Vec<Bin>
+where
+    I: Iterator,
+    F: Fn(&I::Item) -> f64,
+{
+    let mut values: Vec<f64> = iter.map(|item| f(&item)).filter(|v| !v.is_nan()).collect();
+    let edges = match binning {
+        Binning::Edges(edges) => edges,
+        Binning::EqualWidth(count) => {
+            assert!(count > 0, "histogram requires at least one bin");
+            // The range spans the finite values, so infinities can't be counted.
+            values.retain(|v| v.is_finite());
+            if values.is_empty() {
+                return Vec::new();
+            }
+            let min = values.iter().cloned().fold(f64::INFINITY, f64::min);
+            let max = values.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+            if min == max {
+                vec![min, max]
+            } else {
+                // Interpolating the ends doesn't overflow, even when `max - min` does.
+                (0..=count)
+                    .map(|i| {
+                        if i == count {
+                            max
+                        } else {
+                            let t = i as f64 / count as f64;
+                            min * (1.0 - t) + max * t
+                        }
+                    })
+                    .collect()
+            }
+        }
+        Binning::Quantile(count) => {
+            assert!(count > 0, "histogram requires at least one bin");
+            values.retain(|v| v.is_finite());
+            if values.is_empty() {
+                return Vec::new();
+            }
+            values.sort_by(|a, b| a.total_cmp(b));
+            if values[0] == values[values.len() - 1] {
+                vec![values[0], values[0]]
+            } else {
+                (0..=count)
+                    .map(|i| {
+                        let p = 100.0 * i as f64 / count as f64;
+                        percentile_of_sorted(&values, p, Interpolation::Linear)
+                    })
+                    .collect()
+            }
+        }
+    };
+    check_edges(&edges);
+
+    let mut bins: Vec<Bin> = edges
+        .windows(2)
+        .map(|w| Bin {
+            start: w[0],
+            end: w[1],
+            count: 0,
+        })
+        .collect();
+    for value in values {
+        if let Some(index) = bin_index(&edges, value) {
+            bins[index].count += 1;
+        }
+    }
+    bins
+}
+
+pub fn bucket_by<I, F>(iter: I, edges: &[f64], f: F) -> Vec<Bucket<I::Item>>
+where
+    I: Iterator,
+    F: Fn(&I::Item) -> f64,
+{
+    check_edges(edges);
+    let mut buckets: Vec<Bucket<I::Item>> = edges
+        .windows(2)
+        .map(|w| Bucket {
+            start: w[0],
+            end: w[1],
+            items: Vec::new(),
+        })
+        .collect();
+    for item in iter {
+        if let Some(index) = bin_index(edges, f(&item)) {
+            buckets[index].items.push(item);
+        }
+    }
+    buckets
+}
+
+fn check_edges(edges: &[f64]) {
+    assert!(edges.len() >= 2, "bins require at least two edges");
+    assert!(
+        edges.windows(2).all(|w| w[0] <= w[1]),
+        "bin edges must be ascending"
+    );
+}
+
+/// Finds the bin containing the value. Every bin is half-open except the last one.
+fn bin_index(edges: &[f64], value: f64) -> Option<usize> {
+    let last = edges.len() - 1;
+    if value.is_nan() || value < edges[0] || value > edges[last] {
+        return None;
+    }
+    if value == edges[last] {
+        return Some(last - 1);
+    }
+    Some(edges.partition_point(|e| *e <= value) - 1)
+}
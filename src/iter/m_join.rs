@@ -0,0 +1,304 @@
+use super::random::StableHasher;
+use super::spill::{Spill, SpillFile, SpillReader, SpillWriter};
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// The count of partitions each input is split into by one pass.
+const PARTITIONS: usize = 16;
+
+/// The deepest repartitioning. Deeper partitions are joined in memory even if they're over the budget.
+const MAX_DEPTH: usize = 4;
+
+/// Hashes with the depth, so that each repartitioning splits a partition differently.
+fn partition_of<K: Hash>(key: &K, depth: usize) -> usize {
+    (StableHasher::hash_one(&(depth, key)) % PARTITIONS as u64) as usize
+}
+
+struct Partitions {
+    // The writers are dropped before their files are removed.
+    writers: Vec<SpillWriter>,
+    files: Vec<SpillFile>,
+}
+
+impl Partitions {
+    fn create(temp_dir: &Path) -> io::Result<Self> {
+        let mut partitions = Partitions {
+            files: Vec::with_capacity(PARTITIONS),
+            writers: Vec::with_capacity(PARTITIONS),
+        };
+        for _ in 0..PARTITIONS {
+            let (file, writer) = SpillFile::create(temp_dir)?;
+            partitions.files.push(file);
+            partitions.writers.push(writer);
+        }
+        Ok(partitions)
+    }
+
+    /// Finishes the files, and returns each of them with its count of records and encoded bytes.
+    fn finish(self) -> io::Result<Vec<(SpillFile, usize, usize)>> {
+        let mut finished = Vec::with_capacity(PARTITIONS);
+        for (file, writer) in self.files.into_iter().zip(self.writers) {
+            let (records, bytes) = (writer.records(), writer.bytes());
+            writer.finish()?;
+            finished.push((file, records, bytes));
+        }
+        Ok(finished)
+    }
+}
+
+/// A pair of partitions which hold the elements with the same key hashes.
+struct Pending {
+    build: SpillFile,
+    build_bytes: usize,
+    probe: SpillFile,
+    depth: usize,
+}
+
+fn pair_partitions(
+    build: Partitions,
+    probe: Partitions,
+    depth: usize,
+    pending: &mut Vec<Pending>,
+) -> io::Result<()> {
+    for ((build, build_records, build_bytes), (probe, probe_records, _)) in
+        build.finish()?.into_iter().zip(probe.finish()?)
+    {
+        // An inner join of an empty partition is empty.
+        if build_records > 0 && probe_records > 0 {
+            pending.push(Pending {
+                build,
+                build_bytes,
+                probe,
+                depth,
+            });
+        }
+    }
+    Ok(())
+}
+
+enum Probe<I> {
+    Source(I),
+    /// The file is kept until its partition is joined, so it's removed after that.
+    File {
+        reader: SpillReader,
+        _file: SpillFile,
+    },
+    Done,
+}
+
+/// Joins two sequences by a hash table of the elements of the second one. When they don't fit in
+/// the memory budget, both sequences are partitioned by key hash into temporary files, and the
+/// partitions are joined one by one.
+pub struct GraceJoinIterator<I: Iterator, B, K, FI, FU, FR> {
+    key: FI,
+    other_key: FU,
+    result: FR,
+    memory_budget: usize,
+    temp_dir: PathBuf,
+    pending: Vec<Pending>,
+    table: HashMap<K, Vec<B>>,
+    probe: Probe<I>,
+    current: Option<(I::Item, K)>,
+    matched: usize,
+}
+
+impl<I, B, K, FI, FU, FR, R> GraceJoinIterator<I, B, K, FI, FU, FR>
+where
+    I: Iterator,
+    I::Item: Spill,
+    B: Spill,
+    K: Eq + Hash,
+    FI: Fn(&I::Item) -> K,
+    FU: Fn(&B) -> K,
+    FR: FnMut(&I::Item, &B) -> R,
+{
+    fn next_probe(&mut self) -> io::Result<Option<I::Item>> {
+        match &mut self.probe {
+            Probe::Source(iter) => Ok(iter.next()),
+            Probe::File { reader, .. } => reader.read(),
+            Probe::Done => Ok(None),
+        }
+    }
+
+    /// Loads the hash table of the next pair of partitions, splitting it again while it's over the budget.
+    /// It returns `false` when there are no more partitions.
+    fn next_partition(&mut self) -> io::Result<bool> {
+        self.probe = Probe::Done;
+        self.table.clear();
+        while let Some(mut partition) = self.pending.pop() {
+            if partition.build_bytes > self.memory_budget && partition.depth < MAX_DEPTH {
+                partition = match self.repartition(partition)? {
+                    Some(partition) => partition,
+                    None => continue,
+                };
+            }
+            let mut reader = partition.build.open()?;
+            while let Some(item) = reader.read::<B>()? {
+                self.table
+                    .entry((self.other_key)(&item))
+                    .or_default()
+                    .push(item);
+            }
+            self.probe = Probe::File {
+                reader: partition.probe.open()?,
+                _file: partition.probe,
+            };
+            return Ok(true);
+        }
+        Ok(false)
+    }
+
+    /// Splits a pair of partitions by the hash at the next depth. When every element of the build side
+    /// lands in the same child, as with a single hot key, splitting again can't make it smaller,
+    /// so the pair is given back to be joined in memory.
+    fn repartition(&mut self, partition: Pending) -> io::Result<Option<Pending>> {
+        let depth = partition.depth + 1;
+        let mut build = Partitions::create(&self.temp_dir)?;
+        let mut reader = partition.build.open()?;
+        while let Some(bytes) = reader.read_encoded()? {
+            let key = (self.other_key)(&B::decode(bytes)?);
+            build.writers[partition_of(&key, depth)].write_encoded(bytes)?;
+        }
+        if build.writers.iter().filter(|w| w.records() > 0).count() <= 1 {
+            return Ok(Some(partition));
+        }
+        let mut probe = Partitions::create(&self.temp_dir)?;
+        let mut reader = partition.probe.open()?;
+        while let Some(bytes) = reader.read_encoded()? {
+            let key = (self.key)(&I::Item::decode(bytes)?);
+            probe.writers[partition_of(&key, depth)].write_encoded(bytes)?;
+        }
+        pair_partitions(build, probe, depth, &mut self.pending)?;
+        Ok(None)
+    }
+
+    fn fail(&mut self, error: io::Error) -> Option<io::Result<R>> {
+        self.pending.clear();
+        self.probe = Probe::Done;
+        self.current = None;
+        Some(Err(error))
+    }
+}
+
+impl<I, B, K, FI, FU, FR, R> Iterator for GraceJoinIterator<I, B, K, FI, FU, FR>
+where
+    I: Iterator,
+    I::Item: Spill,
+    B: Spill,
+    K: Eq + Hash,
+    FI: Fn(&I::Item) -> K,
+    FU: Fn(&B) -> K,
+    FR: FnMut(&I::Item, &B) -> R,
+{
+    type Item = io::Result<R>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some((item, key)) = &self.current {
+                if let Some(other) = self.table.get(key).and_then(|m| m.get(self.matched)) {
+                    self.matched += 1;
+                    return Some(Ok((self.result)(item, other)));
+                }
+                self.current = None;
+            }
+            match self.next_probe() {
+                Ok(Some(item)) => {
+                    let key = (self.key)(&item);
+                    if self.table.contains_key(&key) {
+                        self.current = Some((item, key));
+                        self.matched = 0;
+                    }
+                }
+                Ok(None) => match self.next_partition() {
+                    Ok(true) => {}
+                    Ok(false) => return None,
+                    Err(e) => return self.fail(e),
+                },
+                Err(e) => return self.fail(e),
+            }
+        }
+    }
+}
+
+/// After the last partition the probe is `Done`, so the source isn't polled again.
+impl<I, B, K, FI, FU, FR, R> std::iter::FusedIterator for GraceJoinIterator<I, B, K, FI, FU, FR>
+where
+    I: Iterator,
+    I::Item: Spill,
+    B: Spill,
+    K: Eq + Hash,
+    FI: Fn(&I::Item) -> K,
+    FU: Fn(&B) -> K,
+    FR: FnMut(&I::Item, &B) -> R,
+{
+}
+
+pub fn grace_join<I, U, K, FI, FU, FR, R>(
+    iter: I,
+    other: U,
+    key: FI,
+    other_key: FU,
+    result: FR,
+    memory_budget: usize,
+    temp_dir: &Path,
+) -> io::Result<GraceJoinIterator<I, U::Item, K, FI, FU, FR>>
+where
+    I: Iterator,
+    I::Item: Spill,
+    U: Iterator,
+    U::Item: Spill,
+    K: Eq + Hash,
+    FI: Fn(&I::Item) -> K,
+    FU: Fn(&U::Item) -> K,
+    FR: FnMut(&I::Item, &U::Item) -> R,
+{
+    let mut join = GraceJoinIterator {
+        key,
+        other_key,
+        result,
+        memory_budget,
+        temp_dir: temp_dir.to_path_buf(),
+        pending: Vec::new(),
+        table: HashMap::new(),
+        probe: Probe::Done,
+        current: None,
+        matched: 0,
+    };
+
+    let mut other = other;
+    let mut buffered = 0;
+    let mut bytes = Vec::new();
+    while let Some(item) = other.next() {
+        bytes.clear();
+        item.encode(&mut bytes);
+        buffered += bytes.len();
+        join.table
+            .entry((join.other_key)(&item))
+            .or_default()
+            .push(item);
+        if buffered > memory_budget {
+            let mut build = Partitions::create(temp_dir)?;
+            for (key, items) in join.table.drain() {
+                let writer = &mut build.writers[partition_of(&key, 0)];
+                for item in items {
+                    writer.write(&item)?;
+                }
+            }
+            for item in other {
+                build.writers[partition_of(&(join.other_key)(&item), 0)].write(&item)?;
+            }
+            let mut probe = Partitions::create(temp_dir)?;
+            for item in iter {
+                probe.writers[partition_of(&(join.key)(&item), 0)].write(&item)?;
+            }
+            pair_partitions(build, probe, 0, &mut join.pending)?;
+            return Ok(join);
+        }
+    }
+
+    // The second sequence fits in memory, so the first one is streamed without spilling.
+    join.probe = Probe::Source(iter);
+    Ok(join)
+}
@@ -0,0 +1,196 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+/// Merges two sequences which are already sorted by a key.
+///
+/// The key of each peeked head is cached, so it is computed once per element.
+pub struct MergeSortedIterator<I: Iterator, U: Iterator<Item = I::Item>, K, F> {
+    first: std::iter::Peekable<I>,
+    second: std::iter::Peekable<U>,
+    first_key: Option<K>,
+    second_key: Option<K>,
+    key: F,
+}
+
+impl<I, U, K, F> Clone for MergeSortedIterator<I, U, K, F>
+where
+    I: Iterator + Clone,
+    U: Iterator<Item = I::Item> + Clone,
+    I::Item: Clone,
+    K: Clone,
+    F: Clone,
+{
+    fn clone(&self) -> Self {
+        MergeSortedIterator {
+            first: self.first.clone(),
+            second: self.second.clone(),
+            first_key: self.first_key.clone(),
+            second_key: self.second_key.clone(),
+            key: self.key.clone(),
+        }
+    }
+}
+
+impl<I, U, K, F> Iterator for MergeSortedIterator<I, U, K, F>
+where
+    I: Iterator,
+    U: Iterator<Item = I::Item>,
+    K: Ord,
+    F: Fn(&I::Item) -> K,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let key = &self.key;
+        if self.first_key.is_none() {
+            self.first_key = self.first.peek().map(key);
+        }
+        if self.second_key.is_none() {
+            self.second_key = self.second.peek().map(key);
+        }
+        let take_second = match (&self.first_key, &self.second_key) {
+            (Some(a), Some(b)) => b < a,
+            (None, Some(_)) => true,
+            _ => false,
+        };
+        if take_second {
+            self.second_key = None;
+            self.second.next()
+        } else {
+            self.first_key = None;
+            self.first.next()
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (first_lower, first_upper) = self.first.size_hint();
+        let (second_lower, second_upper) = self.second.size_hint();
+        let upper = match (first_upper, second_upper) {
+            (Some(x), Some(y)) => x.checked_add(y),
+            _ => None,
+        };
+        (first_lower.saturating_add(second_lower), upper)
+    }
+}
+
+pub fn merge_sorted<I, U, K, F>(iter: I, other: U, key: F) -> MergeSortedIterator<I, U, K, F>
+where
+    I: Iterator,
+    U: Iterator<Item = I::Item>,
+    K: Ord,
+    F: Fn(&I::Item) -> K,
+{
+    MergeSortedIterator {
+        first: iter.peekable(),
+        second: other.peekable(),
+        first_key: None,
+        second_key: None,
+        key,
+    }
+}
+
+/// Merges any number of sequences which are already sorted by a key, using a binary heap of their heads.
+pub struct MergeSortedAllIterator<I: Iterator, K, F> {
+    sources: Vec<I>,
+    heads: Vec<Option<I::Item>>,
+    heap: BinaryHeap<Reverse<(K, usize)>>,
+    key: F,
+}
+
+impl<I, K, F> Clone for MergeSortedAllIterator<I, K, F>
+where
+    I: Iterator + Clone,
+    I::Item: Clone,
+    K: Clone,
+    F: Clone,
+{
+    fn clone(&self) -> Self {
+        MergeSortedAllIterator {
+            sources: self.sources.clone(),
+            heads: self.heads.clone(),
+            heap: self.heap.clone(),
+            key: self.key.clone(),
+        }
+    }
+}
+
+impl<I, K, F> MergeSortedAllIterator<I, K, F>
+where
+    I: Iterator,
+    K: Ord,
+    F: Fn(&I::Item) -> K,
+{
+    fn advance(&mut self, source: usize) {
+        if let Some(item) = self.sources[source].next() {
+            // Ties are broken by the source index, which keeps the merge stable.
+            self.heap.push(Reverse(((self.key)(&item), source)));
+            self.heads[source] = Some(item);
+        }
+    }
+}
+
+impl<I, K, F> Iterator for MergeSortedAllIterator<I, K, F>
+where
+    I: Iterator,
+    K: Ord,
+    F: Fn(&I::Item) -> K,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let Reverse((_, source)) = self.heap.pop()?;
+        let item = self.heads[source].take();
+        self.advance(source);
+        item
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.sources.iter().fold(
+            (self.heap.len(), Some(self.heap.len())),
+            |(lower, upper), source| {
+                let (source_lower, source_upper) = source.size_hint();
+                (
+                    lower.saturating_add(source_lower),
+                    upper.and_then(|x| x.checked_add(source_upper?)),
+                )
+            },
+        )
+    }
+}
+
+/// Merges several sequences which are already sorted in ascending order of a key, in one lazy pass.
+///
+/// Elements with equal keys are yielded in the order of their sources.
+///
+/// # Examples
+///
+/// ```
+/// use linq::iter::merge_sorted_all;
+///
+/// let shards = vec![vec![1, 4, 7], vec![2, 5, 8], vec![3, 6, 9]];
+/// let e: Vec<i32> = merge_sorted_all(shards, |p| *p).collect();
+/// assert_eq!(e, (1..10).collect::<Vec<_>>());
+/// ```
+pub fn merge_sorted_all<S, K, F>(
+    sources: S,
+    key: F,
+) -> MergeSortedAllIterator<<S::Item as IntoIterator>::IntoIter, K, F>
+where
+    S: IntoIterator,
+    S::Item: IntoIterator,
+    K: Ord,
+    F: Fn(&<S::Item as IntoIterator>::Item) -> K,
+{
+    let sources: Vec<_> = sources.into_iter().map(IntoIterator::into_iter).collect();
+    let count = sources.len();
+    let mut iter = MergeSortedAllIterator {
+        sources,
+        heads: (0..count).map(|_| None).collect(),
+        heap: BinaryHeap::with_capacity(count),
+        key,
+    };
+    for source in 0..count {
+        iter.advance(source);
+    }
+    iter
+}
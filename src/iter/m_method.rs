@@ -1,16 +1,35 @@
+use super::m_zip::{zip_longest, EitherOrBoth};
+
 pub fn single<I: Iterator>(mut iter: I) -> Option<I::Item> {
-    match iter.next() {
-        Some(item) => {
-            if iter.next().is_none() {
-                Some(item)
-            } else {
-                None
-            }
-        }
-        None => None,
-    }
+    iter.next().filter(|_| iter.next().is_none())
 }
 
 pub fn contains<I: Iterator<Item = V>, V: Eq>(mut iter: I, value: &V) -> bool {
     iter.any(|item| &item == value)
 }
+
+/// The index where two sequences diverge, with the values found there.
+pub type SequenceDifference<A, B> = (usize, EitherOrBoth<A, B>);
+
+pub fn sequence_equal_by<I: Iterator, U: IntoIterator, F>(iter: I, other: U, comparer: F) -> bool
+where
+    F: FnMut(&I::Item, &U::Item) -> bool,
+{
+    first_difference_by(iter, other, comparer).is_none()
+}
+
+pub fn first_difference_by<I: Iterator, U: IntoIterator, F>(
+    iter: I,
+    other: U,
+    mut comparer: F,
+) -> Option<SequenceDifference<I::Item, U::Item>>
+where
+    F: FnMut(&I::Item, &U::Item) -> bool,
+{
+    zip_longest(iter, other)
+        .enumerate()
+        .find(|(_, item)| match item {
+            EitherOrBoth::Both(a, b) => !comparer(a, b),
+            _ => true,
+        })
+}
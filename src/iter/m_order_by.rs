@@ -1,34 +1,313 @@
+use std::cmp::Ordering;
+use std::iter::FusedIterator;
+
+/// Decides how the key of an element is computed while sorting.
+pub trait SortKey<T> {
+    /// The part of the key which is stored next to each element.
+    type Key;
+
+    fn key(&self, item: &T) -> Self::Key;
+
+    fn compare(&self, a: &Entry<Self::Key, T>, b: &Entry<Self::Key, T>) -> Ordering;
+}
+
+/// Calls the key selector once per element, and stores the keys until the elements are yielded.
+#[derive(Clone)]
+pub struct CachedKey<F>(F);
+
+impl<T, K: Ord, F: Fn(&T) -> K> SortKey<T> for CachedKey<F> {
+    type Key = K;
+
+    fn key(&self, item: &T) -> K {
+        (self.0)(item)
+    }
+
+    fn compare(&self, a: &Entry<K, T>, b: &Entry<K, T>) -> Ordering {
+        a.key.cmp(&b.key)
+    }
+}
+
+/// Calls the key selector on every comparison, which saves memory when keys are cheap to compute.
+#[derive(Clone)]
+pub struct UncachedKey<F>(F);
+
+impl<T, K: Ord, F: Fn(&T) -> K> SortKey<T> for UncachedKey<F> {
+    type Key = ();
+
+    fn key(&self, _item: &T) {}
+
+    fn compare(&self, a: &Entry<(), T>, b: &Entry<(), T>) -> Ordering {
+        (self.0)(&a.item).cmp(&(self.0)(&b.item))
+    }
+}
+
+/// Borrows the key from the element on every comparison, so nothing is cloned or stored.
+#[derive(Clone)]
+pub struct BorrowedKey<F>(F);
+
+impl<T, K: Ord + ?Sized, F> SortKey<T> for BorrowedKey<F>
+where
+    F: for<'a> Fn(&'a T) -> &'a K,
+{
+    type Key = ();
+
+    fn key(&self, _item: &T) {}
+
+    fn compare(&self, a: &Entry<(), T>, b: &Entry<(), T>) -> Ordering {
+        (self.0)(&a.item).cmp((self.0)(&b.item))
+    }
+}
+
+#[derive(Clone)]
+pub struct Entry<K, T> {
+    pub(super) key: K,
+    index: usize,
+    pub(super) item: T,
+}
+
+/// Yields elements in key order, popping them one at a time from a binary heap.
+///
+/// The heap is built in linear time, so taking only the first few elements doesn't sort the whole sequence.
+/// In ascending order, elements with equal keys keep their source order; descending order is its exact reverse.
+///
+/// Taking an element from the back, taking more than an eighth of the elements, or consuming the iterator
+/// with `fold` sorts the remaining elements at once, which is faster than popping them one by one.
 #[derive(Clone)]
-pub enum OrderedIterator<T> {
-    Ascending(std::vec::IntoIter<T>),
-    Descending(std::iter::Rev<std::vec::IntoIter<T>>),
+pub struct OrderedIterator<T, S: SortKey<T>> {
+    heap: Vec<Entry<S::Key, T>>,
+    sorted: std::vec::IntoIter<Entry<S::Key, T>>,
+    sort_key: S,
+    descending: bool,
+    sort_at: usize,
 }
 
-impl<T> Iterator for OrderedIterator<T> {
+impl<T, S: SortKey<T>> OrderedIterator<T, S> {
+    fn new(heap: Vec<Entry<S::Key, T>>, sort_key: S, descending: bool) -> Self {
+        let len = heap.len();
+        let mut iter = OrderedIterator {
+            heap,
+            sorted: Vec::new().into_iter(),
+            sort_key,
+            descending,
+            sort_at: len - len / 8,
+        };
+        for position in (0..iter.heap.len() / 2).rev() {
+            iter.sift_down(position, false);
+        }
+        iter
+    }
+
+    /// Compares entries in the order they are yielded: by key, then by source position.
+    ///
+    /// Positions are unique, so this is a total order which is `Equal` only for the same entry.
+    fn order(&self, a: &Entry<S::Key, T>, b: &Entry<S::Key, T>) -> Ordering {
+        let ordering = self
+            .sort_key
+            .compare(a, b)
+            .then_with(|| a.index.cmp(&b.index));
+        if self.descending {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    }
+
+    /// Checks whether the entry `a` is yielded before `b`.
+    fn precedes(&self, a: &Entry<S::Key, T>, b: &Entry<S::Key, T>) -> bool {
+        self.order(a, b) == Ordering::Less
+    }
+
+    /// Sorts the remaining elements at once, and yields each of them with its stored key.
+    pub(super) fn into_keyed(mut self) -> impl Iterator<Item = (S::Key, T)> {
+        self.sort_remaining();
+        self.sorted.map(|entry| (entry.key, entry.item))
+    }
+
+    /// Moves the entries left in the heap to `sorted`, in the order they are yielded.
+    fn sort_remaining(&mut self) {
+        if self.heap.is_empty() {
+            return;
+        }
+        // The heap is only filled before the first sort, so nothing is left in `sorted`.
+        let mut entries = std::mem::take(&mut self.heap);
+        entries.sort_unstable_by(|a, b| self.order(a, b));
+        self.sorted = entries.into_iter();
+    }
+
+    /// Checks whether `a` should be nearer to the root than `b`. A reversed heap has the last entry at its root.
+    fn above(&self, a: &Entry<S::Key, T>, b: &Entry<S::Key, T>, reversed: bool) -> bool {
+        if reversed {
+            self.precedes(b, a)
+        } else {
+            self.precedes(a, b)
+        }
+    }
+
+    fn sift_down(&mut self, mut position: usize, reversed: bool) {
+        let len = self.heap.len();
+        loop {
+            let left = 2 * position + 1;
+            if left >= len {
+                return;
+            }
+            let right = left + 1;
+            let child = if right < len && self.above(&self.heap[right], &self.heap[left], reversed)
+            {
+                right
+            } else {
+                left
+            };
+            if !self.above(&self.heap[child], &self.heap[position], reversed) {
+                return;
+            }
+            self.heap.swap(child, position);
+            position = child;
+        }
+    }
+
+    fn sift_up(&mut self, mut position: usize, reversed: bool) {
+        while position > 0 {
+            let parent = (position - 1) / 2;
+            if !self.above(&self.heap[position], &self.heap[parent], reversed) {
+                break;
+            }
+            self.heap.swap(position, parent);
+            position = parent;
+        }
+    }
+}
+
+impl<T, S: SortKey<T>> Iterator for OrderedIterator<T, S> {
     type Item = T;
 
     fn next(&mut self) -> Option<Self::Item> {
-        match self {
-            OrderedIterator::Ascending(ins) => ins.next(),
-            OrderedIterator::Descending(ins) => ins.next(),
+        if self.heap.len() <= self.sort_at {
+            self.sort_remaining();
+        }
+        if self.heap.is_empty() {
+            return self.sorted.next().map(|entry| entry.item);
         }
+        let entry = self.heap.swap_remove(0);
+        self.sift_down(0, false);
+        Some(entry.item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.len();
+        (len, Some(len))
+    }
+
+    fn fold<B, G>(mut self, init: B, f: G) -> B
+    where
+        G: FnMut(B, Self::Item) -> B,
+    {
+        self.sort_remaining();
+        self.sorted.map(|entry| entry.item).fold(init, f)
+    }
+}
+
+impl<T, S: SortKey<T>> DoubleEndedIterator for OrderedIterator<T, S> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.sort_remaining();
+        self.sorted.next_back().map(|entry| entry.item)
+    }
+}
+
+impl<T, S: SortKey<T>> ExactSizeIterator for OrderedIterator<T, S> {
+    fn len(&self) -> usize {
+        self.heap.len() + self.sorted.len()
     }
 }
 
+impl<T, S: SortKey<T>> FusedIterator for OrderedIterator<T, S> {}
+
 pub fn order_by<I: Iterator, K: Ord, F>(
     iter: I,
     func: F,
     descending: bool,
-) -> OrderedIterator<I::Item>
+) -> OrderedIterator<I::Item, CachedKey<F>>
 where
-    F: FnMut(&I::Item) -> K,
+    F: Fn(&I::Item) -> K,
 {
-    let mut temp: Vec<_> = iter.collect();
-    temp.sort_by_key(func);
+    order_by_key(iter, CachedKey(func), descending)
+}
 
-    if descending {
-        OrderedIterator::Descending(temp.into_iter().rev())
-    } else {
-        OrderedIterator::Ascending(temp.into_iter())
+pub fn order_by_uncached<I: Iterator, K: Ord, F>(
+    iter: I,
+    func: F,
+    descending: bool,
+) -> OrderedIterator<I::Item, UncachedKey<F>>
+where
+    F: Fn(&I::Item) -> K,
+{
+    order_by_key(iter, UncachedKey(func), descending)
+}
+
+pub fn order_by_ref<I: Iterator, K: Ord + ?Sized, F>(
+    iter: I,
+    func: F,
+    descending: bool,
+) -> OrderedIterator<I::Item, BorrowedKey<F>>
+where
+    F: for<'a> Fn(&'a I::Item) -> &'a K,
+{
+    order_by_key(iter, BorrowedKey(func), descending)
+}
+
+pub fn order_by_key<I: Iterator, S>(
+    iter: I,
+    sort_key: S,
+    descending: bool,
+) -> OrderedIterator<I::Item, S>
+where
+    S: SortKey<I::Item>,
+{
+    let heap = iter
+        .enumerate()
+        .map(|(index, item)| Entry {
+            key: sort_key.key(&item),
+            index,
+            item,
+        })
+        .collect();
+    OrderedIterator::new(heap, sort_key, descending)
+}
+
+/// Keeps the `count` elements which come first in key order, in a heap bounded to `count` elements whose
+/// root is the last of them.
+pub fn first_k<I: Iterator, K: Ord, F>(
+    iter: I,
+    count: usize,
+    func: F,
+    descending: bool,
+) -> OrderedIterator<I::Item, CachedKey<F>>
+where
+    F: Fn(&I::Item) -> K,
+{
+    // `count` may be far larger than the source, e.g. `usize::MAX` to keep everything.
+    let capacity = count.min(iter.size_hint().0);
+    let mut bounded = OrderedIterator {
+        heap: Vec::with_capacity(capacity),
+        sorted: Vec::new().into_iter(),
+        sort_key: CachedKey(func),
+        descending,
+        sort_at: 0,
+    };
+    if count > 0 {
+        for (index, item) in iter.enumerate() {
+            let entry = Entry {
+                key: bounded.sort_key.key(&item),
+                index,
+                item,
+            };
+            if bounded.heap.len() < count {
+                bounded.heap.push(entry);
+                bounded.sift_up(bounded.heap.len() - 1, true);
+            } else if bounded.precedes(&entry, &bounded.heap[0]) {
+                bounded.heap[0] = entry;
+                bounded.sift_down(0, true);
+            }
+        }
     }
+    OrderedIterator::new(bounded.heap, bounded.sort_key, descending)
 }
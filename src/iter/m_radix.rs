@@ -0,0 +1,144 @@
+/// A fixed-width key which can be sorted by LSD radix sort, one byte at a time.
+///
+/// Comparing the bytes from the most significant one must give the same ordering as `Ord`.
+pub trait RadixKey {
+    /// The count of bytes of the key.
+    const BYTES: usize;
+
+    /// Returns the byte at `index`, where index `0` is the least significant byte.
+    fn radix_byte(&self, index: usize) -> u8;
+}
+
+macro_rules! unsigned_radix_key {
+    ($($t:ty),*) => {
+        $(
+            impl RadixKey for $t {
+                const BYTES: usize = std::mem::size_of::<$t>();
+
+                fn radix_byte(&self, index: usize) -> u8 {
+                    (*self >> (8 * index)) as u8
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! signed_radix_key {
+    ($($t:ty => $u:ty),*) => {
+        $(
+            impl RadixKey for $t {
+                const BYTES: usize = std::mem::size_of::<$t>();
+
+                fn radix_byte(&self, index: usize) -> u8 {
+                    // Flipping the sign bit moves negative numbers below positive ones.
+                    ((*self as $u ^ (1 << (<$u>::BITS - 1))) >> (8 * index)) as u8
+                }
+            }
+        )*
+    };
+}
+
+unsigned_radix_key!(u8, u16, u32, u64, u128, usize);
+signed_radix_key!(i8 => u8, i16 => u16, i32 => u32, i64 => u64, i128 => u128, isize => usize);
+
+impl RadixKey for bool {
+    const BYTES: usize = 1;
+
+    fn radix_byte(&self, _index: usize) -> u8 {
+        *self as u8
+    }
+}
+
+impl RadixKey for char {
+    const BYTES: usize = 4;
+
+    fn radix_byte(&self, index: usize) -> u8 {
+        (*self as u32).radix_byte(index)
+    }
+}
+
+impl<A: RadixKey, B: RadixKey> RadixKey for (A, B) {
+    const BYTES: usize = A::BYTES + B::BYTES;
+
+    fn radix_byte(&self, index: usize) -> u8 {
+        if index < B::BYTES {
+            self.1.radix_byte(index)
+        } else {
+            self.0.radix_byte(index - B::BYTES)
+        }
+    }
+}
+
+impl<A: RadixKey, B: RadixKey, C: RadixKey> RadixKey for (A, B, C) {
+    const BYTES: usize = A::BYTES + B::BYTES + C::BYTES;
+
+    fn radix_byte(&self, index: usize) -> u8 {
+        if index < C::BYTES {
+            self.2.radix_byte(index)
+        } else {
+            (&self.0, &self.1).radix_byte(index - C::BYTES)
+        }
+    }
+}
+
+impl<K: RadixKey> RadixKey for &K {
+    const BYTES: usize = K::BYTES;
+
+    fn radix_byte(&self, index: usize) -> u8 {
+        (*self).radix_byte(index)
+    }
+}
+
+pub type RadixOrderedIterator<T> = std::vec::IntoIter<T>;
+
+pub fn order_by_radix<I: Iterator, K: RadixKey, F>(
+    iter: I,
+    func: F,
+    descending: bool,
+) -> RadixOrderedIterator<I::Item>
+where
+    F: Fn(&I::Item) -> K,
+{
+    let mut keys = Vec::new();
+    let mut items: Vec<Option<I::Item>> = Vec::new();
+    for item in iter {
+        keys.push(func(&item));
+        items.push(Some(item));
+    }
+
+    // Each pass is a stable counting sort of the permutation by one byte, so equal keys keep the source order.
+    // Descending order is the exact reverse, like `order_by_descending`.
+    let mut order: Vec<usize> = (0..keys.len()).collect();
+    let mut buffer = vec![0; keys.len()];
+    for byte in 0..K::BYTES {
+        let mut offsets = [0usize; 256];
+        for key in &keys {
+            offsets[key.radix_byte(byte) as usize] += 1;
+        }
+        // All keys share this byte, so the pass wouldn't move anything.
+        if offsets.contains(&keys.len()) {
+            continue;
+        }
+        let mut start = 0;
+        for offset in offsets.iter_mut() {
+            let count = *offset;
+            *offset = start;
+            start += count;
+        }
+        for index in &order {
+            let slot = &mut offsets[keys[*index].radix_byte(byte) as usize];
+            buffer[*slot] = *index;
+            *slot += 1;
+        }
+        std::mem::swap(&mut order, &mut buffer);
+    }
+
+    if descending {
+        order.reverse();
+    }
+    order
+        .into_iter()
+        .map(|index| items[index].take().unwrap())
+        .collect::<Vec<_>>()
+        .into_iter()
+}
@@ -0,0 +1,40 @@
+use super::random::SplitMix64;
+
+pub type SampleIterator<T> = std::vec::IntoIter<T>;
+
+pub type ShuffleIterator<T> = std::vec::IntoIter<T>;
+
+pub fn sample<I: Iterator>(iter: I, count: usize, seed: u64) -> SampleIterator<I::Item> {
+    let mut random = SplitMix64::new(seed);
+    // The reservoir never holds more elements than the source has.
+    let mut reservoir = Vec::with_capacity(count.min(iter.size_hint().0));
+    if count == 0 {
+        return reservoir.into_iter();
+    }
+    for (index, item) in iter.enumerate() {
+        if index < count {
+            reservoir.push(item);
+        } else {
+            let slot = random.next_below(index as u64 + 1) as usize;
+            if slot < count {
+                reservoir[slot] = item;
+            }
+        }
+    }
+    reservoir.into_iter()
+}
+
+pub fn shuffle<I: Iterator>(iter: I, seed: u64) -> ShuffleIterator<I::Item> {
+    let mut random = SplitMix64::new(seed);
+    let mut items: Vec<_> = iter.collect();
+    // Fisher-Yates shuffle.
+    for index in (1..items.len()).rev() {
+        let other = random.next_below(index as u64 + 1) as usize;
+        items.swap(index, other);
+    }
+    items.into_iter()
+}
+
+pub fn random_element<I: Iterator>(iter: I, seed: u64) -> Option<I::Item> {
+    sample(iter, 1, seed).next()
+}
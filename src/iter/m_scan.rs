@@ -0,0 +1,180 @@
+use std::collections::VecDeque;
+
+#[derive(Clone)]
+pub struct ScanAggregateIterator<I, B, F> {
+    source: I,
+    accumulator: B,
+    func: F,
+}
+
+impl<I, B, F> Iterator for ScanAggregateIterator<I, B, F>
+where
+    I: Iterator,
+    B: Clone,
+    F: FnMut(&B, I::Item) -> B,
+{
+    type Item = B;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.source.next()?;
+        self.accumulator = (self.func)(&self.accumulator, item);
+        Some(self.accumulator.clone())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.source.size_hint()
+    }
+}
+
+pub fn scan_aggregate<I, B, F>(iter: I, seed: B, func: F) -> ScanAggregateIterator<I, B, F>
+where
+    I: Iterator,
+    B: Clone,
+    F: FnMut(&B, I::Item) -> B,
+{
+    ScanAggregateIterator {
+        source: iter,
+        accumulator: seed,
+        func,
+    }
+}
+
+#[derive(Clone)]
+pub struct PairwiseIterator<I: Iterator> {
+    source: I,
+    previous: Option<I::Item>,
+}
+
+impl<I> Iterator for PairwiseIterator<I>
+where
+    I: Iterator,
+    I::Item: Clone,
+{
+    type Item = (I::Item, I::Item);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.previous.is_none() {
+            self.previous = Some(self.source.next()?);
+        }
+        let current = self.source.next()?;
+        let previous = self.previous.replace(current.clone()).unwrap();
+        Some((previous, current))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.source.size_hint();
+        if self.previous.is_some() {
+            (lower, upper)
+        } else {
+            (lower.saturating_sub(1), upper.map(|x| x.saturating_sub(1)))
+        }
+    }
+}
+
+pub fn pairwise<I>(iter: I) -> PairwiseIterator<I>
+where
+    I: Iterator,
+    I::Item: Clone,
+{
+    PairwiseIterator {
+        source: iter,
+        previous: None,
+    }
+}
+
+#[derive(Clone)]
+pub struct LagIterator<I: Iterator> {
+    source: I,
+    offset: usize,
+    buffer: VecDeque<I::Item>,
+}
+
+impl<I> Iterator for LagIterator<I>
+where
+    I: Iterator,
+    I::Item: Clone,
+{
+    type Item = (I::Item, Option<I::Item>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.source.next()?;
+        self.buffer.push_back(item.clone());
+        let lagged = if self.buffer.len() > self.offset {
+            self.buffer.pop_front()
+        } else {
+            None
+        };
+        Some((item, lagged))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.source.size_hint()
+    }
+}
+
+pub fn lag<I>(iter: I, offset: usize) -> LagIterator<I>
+where
+    I: Iterator,
+    I::Item: Clone,
+{
+    // The buffer never holds more than the source has, however large the offset is.
+    let capacity = offset.saturating_add(1).min(iter.size_hint().0);
+    LagIterator {
+        source: iter,
+        offset,
+        buffer: VecDeque::with_capacity(capacity),
+    }
+}
+
+#[derive(Clone)]
+pub struct LeadIterator<I: Iterator> {
+    source: std::iter::Fuse<I>,
+    offset: usize,
+    buffer: VecDeque<I::Item>,
+}
+
+impl<I> Iterator for LeadIterator<I>
+where
+    I: Iterator,
+    I::Item: Clone,
+{
+    type Item = (I::Item, Option<I::Item>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.buffer.len() <= self.offset {
+            match self.source.next() {
+                Some(item) => self.buffer.push_back(item),
+                None => break,
+            }
+        }
+        let item = self.buffer.pop_front()?;
+        let led = if self.offset == 0 {
+            Some(item.clone())
+        } else {
+            self.buffer.get(self.offset - 1).cloned()
+        };
+        Some((item, led))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.source.size_hint();
+        let buffered = self.buffer.len();
+        (
+            lower.saturating_add(buffered),
+            upper.and_then(|x| x.checked_add(buffered)),
+        )
+    }
+}
+
+pub fn lead<I>(iter: I, offset: usize) -> LeadIterator<I>
+where
+    I: Iterator,
+    I::Item: Clone,
+{
+    let capacity = offset.saturating_add(1).min(iter.size_hint().0);
+    LeadIterator {
+        source: iter.fuse(),
+        offset,
+        buffer: VecDeque::with_capacity(capacity),
+    }
+}
@@ -1,3 +1,5 @@
+use std::iter::FusedIterator;
+
 #[derive(Clone)]
 pub struct SelectManyIterator<I: Iterator, T, FC, FR> {
     iter: I,
@@ -17,7 +19,7 @@ where
 
     fn next(&mut self) -> Option<Self::Item> {
         if let Some(cit) = &mut self.citer {
-            for x in cit {
+            if let Some(x) = cit.next() {
                 return Some((self.fr)(self.ci.as_ref().unwrap().clone(), x));
             }
         }
@@ -29,13 +31,34 @@ where
             self.ci = Some(ci.as_ref().unwrap().clone());
             self.citer = Some((self.fc)(ci.unwrap()));
             if let Some(cit) = &mut self.citer {
-                for x in cit {
+                if let Some(x) = cit.next() {
                     return Some((self.fr)(self.ci.as_ref().unwrap().clone(), x));
                 }
             }
         }
         None
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self
+            .citer
+            .as_ref()
+            .map_or((0, Some(0)), |cit| cit.size_hint());
+        match self.iter.size_hint() {
+            (_, Some(0)) => (lower, upper),
+            _ => (lower, None),
+        }
+    }
+}
+
+impl<I, T, FC, FR, R> FusedIterator for SelectManyIterator<I, T, FC, FR>
+where
+    I: FusedIterator,
+    I::Item: Clone,
+    T: FusedIterator,
+    FC: FnMut(I::Item) -> T,
+    FR: FnMut(I::Item, T::Item) -> R,
+{
 }
 
 pub fn select_many<I: Iterator, T: Iterator, FC, FR, R>(
@@ -60,6 +83,7 @@ where
 pub struct SelectManySingleIterator<I, T, F> {
     iter: I,
     citer: Option<T>,
+    back_citer: Option<T>,
     func: F,
 }
 
@@ -70,23 +94,67 @@ where
     type Item = T::Item;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if let Some(cit) = &mut self.citer {
-            for x in cit {
-                return Some(x);
+        loop {
+            if let Some(cit) = &mut self.citer {
+                if let Some(x) = cit.next() {
+                    return Some(x);
+                }
+                self.citer = None;
+            }
+            match self.iter.next() {
+                Some(tit) => self.citer = Some((self.func)(tit)),
+                None => return self.back_citer.as_mut()?.next(),
             }
         }
-        for tit in &mut self.iter {
-            self.citer = Some((self.func)(tit));
-            if let Some(cit) = &mut self.citer {
-                for x in cit {
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let front = self
+            .citer
+            .as_ref()
+            .map_or((0, Some(0)), |cit| cit.size_hint());
+        let back = self
+            .back_citer
+            .as_ref()
+            .map_or((0, Some(0)), |cit| cit.size_hint());
+        let lower = front.0.saturating_add(back.0);
+        match (self.iter.size_hint(), front.1, back.1) {
+            ((_, Some(0)), Some(front), Some(back)) => (lower, front.checked_add(back)),
+            _ => (lower, None),
+        }
+    }
+}
+
+impl<I, T, F> DoubleEndedIterator for SelectManySingleIterator<I, T, F>
+where
+    I: DoubleEndedIterator,
+    T: DoubleEndedIterator,
+    F: FnMut(I::Item) -> T,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(cit) = &mut self.back_citer {
+                if let Some(x) = cit.next_back() {
                     return Some(x);
                 }
+                self.back_citer = None;
+            }
+            match self.iter.next_back() {
+                Some(tit) => self.back_citer = Some((self.func)(tit)),
+                None => return self.citer.as_mut()?.next_back(),
             }
         }
-        None
     }
 }
 
+impl<I, T, F> FusedIterator for SelectManySingleIterator<I, T, F>
+where
+    I: FusedIterator,
+    T: FusedIterator,
+    F: FnMut(I::Item) -> T,
+{
+}
+
 pub fn select_many_single<I: Iterator, T: Iterator, F>(
     iter: I,
     func: F,
@@ -97,6 +165,7 @@ where
     SelectManySingleIterator {
         iter,
         citer: None,
+        back_citer: None,
         func,
     }
 }
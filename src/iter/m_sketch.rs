@@ -0,0 +1,272 @@
+use super::random::{SplitMix64, StableHasher};
+use std::hash::Hash;
+
+/// A HyperLogLog sketch estimating the number of distinct items in a stream.
+///
+/// It uses `2^precision` one-byte registers, and its relative error is about `1.04 / sqrt(2^precision)`.
+/// Sketches with the same precision can be merged to combine partial results.
+///
+/// Items are hashed by a hash function fixed by this crate rather than `DefaultHasher`, so sketches built by
+/// different programs or Rust versions can be merged, as long as the items' `Hash` implementations agree.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HyperLogLog {
+    precision: u8,
+    registers: Vec<u8>,
+}
+
+impl HyperLogLog {
+    /// Creates an empty sketch.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `precision` is not between 4 and 16.
+    pub fn new(precision: u8) -> Self {
+        assert!(
+            (4..=16).contains(&precision),
+            "precision must be between 4 and 16"
+        );
+        HyperLogLog {
+            precision,
+            registers: vec![0; 1 << precision],
+        }
+    }
+
+    pub fn precision(&self) -> u8 {
+        self.precision
+    }
+
+    /// Adds an item to the sketch.
+    pub fn insert<T: Hash + ?Sized>(&mut self, item: &T) {
+        let hash = StableHasher::hash_one(item);
+
+        let index = (hash >> (64 - self.precision)) as usize;
+        let rest = hash << self.precision;
+        let rank = std::cmp::min(rest.leading_zeros() as u8, 64 - self.precision) + 1;
+        if rank > self.registers[index] {
+            self.registers[index] = rank;
+        }
+    }
+
+    /// Merges another sketch into this one, as if all of its items had been inserted here.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the sketches have different precisions.
+    pub fn merge(&mut self, other: &HyperLogLog) {
+        assert_eq!(
+            self.precision, other.precision,
+            "cannot merge sketches with different precisions"
+        );
+        for (register, other) in self.registers.iter_mut().zip(&other.registers) {
+            *register = std::cmp::max(*register, *other);
+        }
+    }
+
+    /// Estimates the number of distinct items inserted.
+    pub fn count(&self) -> u64 {
+        let m = self.registers.len() as f64;
+        let alpha = match self.registers.len() {
+            16 => 0.673,
+            32 => 0.697,
+            64 => 0.709,
+            _ => 0.7213 / (1.0 + 1.079 / m),
+        };
+        let sum: f64 = self.registers.iter().map(|r| 2f64.powi(-(*r as i32))).sum();
+        let estimate = alpha * m * m / sum;
+
+        let zeros = self.registers.iter().filter(|r| **r == 0).count();
+        if estimate <= 2.5 * m && zeros > 0 {
+            // Linear counting is more accurate for small cardinalities.
+            (m * (m / zeros as f64).ln()).round() as u64
+        } else {
+            estimate.round() as u64
+        }
+    }
+}
+
+impl<T: Hash> Extend<T> for HyperLogLog {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.insert(&item);
+        }
+    }
+}
+
+/// A KLL sketch estimating the quantiles of a stream in memory that grows only logarithmically.
+///
+/// Larger `k` gives more accurate quantiles in exchange for more memory.
+/// Sketches can be merged to combine partial results.
+#[derive(Clone, Debug)]
+pub struct QuantileSketch<T> {
+    k: usize,
+    compactors: Vec<Vec<T>>,
+    size: usize,
+    max_size: usize,
+    count: usize,
+    random: SplitMix64,
+}
+
+impl<T: Ord> QuantileSketch<T> {
+    /// Creates an empty sketch.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `k` is less than 2.
+    pub fn new(k: usize) -> Self {
+        assert!(k >= 2, "k must be at least 2");
+        let mut sketch = QuantileSketch {
+            k,
+            compactors: Vec::new(),
+            size: 0,
+            max_size: 0,
+            count: 0,
+            random: SplitMix64::new(k as u64),
+        };
+        sketch.grow();
+        sketch
+    }
+
+    /// Returns the number of items inserted.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Adds an item to the sketch.
+    pub fn insert(&mut self, item: T) {
+        self.compactors[0].push(item);
+        self.size += 1;
+        self.count += 1;
+        if self.size >= self.max_size {
+            self.compress();
+        }
+    }
+
+    /// Merges another sketch into this one, as if all of its items had been inserted here.
+    pub fn merge(&mut self, other: QuantileSketch<T>) {
+        while self.compactors.len() < other.compactors.len() {
+            self.grow();
+        }
+        for (compactor, other) in self.compactors.iter_mut().zip(other.compactors) {
+            compactor.extend(other);
+        }
+        self.count += other.count;
+        self.size = self.compactors.iter().map(Vec::len).sum();
+        while self.size >= self.max_size {
+            self.compress();
+        }
+    }
+
+    /// Estimates the `q`-th quantile (between 0 and 1), or `None` if the sketch is empty.
+    ///
+    /// Each call sorts the retained items, which are `O(k log(count / k))`.
+    /// Use `quantiles` to estimate several quantiles with a single sort.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `q` is not between 0 and 1.
+    pub fn quantile(&self, q: f64) -> Option<&T> {
+        self.quantiles(&[q]).and_then(|mut result| result.pop())
+    }
+
+    /// Estimates each of the `quantiles` (between 0 and 1), or returns `None` if the sketch is empty.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any quantile is not between 0 and 1.
+    pub fn quantiles(&self, quantiles: &[f64]) -> Option<Vec<&T>> {
+        for q in quantiles {
+            assert!((0.0..=1.0).contains(q), "quantile must be between 0 and 1");
+        }
+        if self.count == 0 {
+            return None;
+        }
+        let mut weighted: Vec<(&T, usize)> = self
+            .compactors
+            .iter()
+            .enumerate()
+            .flat_map(|(height, items)| items.iter().map(move |item| (item, 1 << height)))
+            .collect();
+        weighted.sort_by(|a, b| a.0.cmp(b.0));
+
+        let total: usize = weighted.iter().map(|(_, weight)| weight).sum();
+        let result = quantiles
+            .iter()
+            .map(|q| {
+                let target = q * total as f64;
+                let mut cumulative = 0;
+                for (item, weight) in &weighted {
+                    cumulative += weight;
+                    if cumulative as f64 >= target {
+                        return *item;
+                    }
+                }
+                weighted[weighted.len() - 1].0
+            })
+            .collect();
+        Some(result)
+    }
+
+    fn capacity(&self, height: usize) -> usize {
+        let depth = self.compactors.len() - height - 1;
+        ((2.0f64 / 3.0).powi(depth as i32) * self.k as f64).ceil() as usize + 1
+    }
+
+    fn grow(&mut self) {
+        self.compactors.push(Vec::new());
+        self.max_size = (0..self.compactors.len()).map(|h| self.capacity(h)).sum();
+    }
+
+    fn compress(&mut self) {
+        for height in 0..self.compactors.len() {
+            if self.compactors[height].len() >= self.capacity(height) {
+                if height + 1 >= self.compactors.len() {
+                    self.grow();
+                }
+                // Keep every other item of the sorted compactor, doubling its weight.
+                let mut items = std::mem::take(&mut self.compactors[height]);
+                items.sort();
+                if items.len() % 2 == 1 {
+                    let last = items.pop().unwrap();
+                    self.compactors[height].push(last);
+                }
+                let offset = self.random.next_bool() as usize;
+                let promoted = items.into_iter().skip(offset).step_by(2);
+                self.compactors[height + 1].extend(promoted);
+
+                self.size = self.compactors.iter().map(Vec::len).sum();
+                if self.size < self.max_size {
+                    break;
+                }
+            }
+        }
+    }
+}
+
+impl<T: Ord> Extend<T> for QuantileSketch<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.insert(item);
+        }
+    }
+}
+
+pub fn approx_count_distinct<I>(iter: I, precision: u8) -> u64
+where
+    I: Iterator,
+    I::Item: Hash,
+{
+    let mut sketch = HyperLogLog::new(precision);
+    sketch.extend(iter);
+    sketch.count()
+}
+
+pub fn approx_quantiles<I>(iter: I, k: usize, quantiles: &[f64]) -> Option<Vec<I::Item>>
+where
+    I: Iterator,
+    I::Item: Ord + Clone,
+{
+    let mut sketch = QuantileSketch::new(k);
+    sketch.extend(iter);
+    let result = sketch.quantiles(quantiles)?;
+    Some(result.into_iter().cloned().collect())
+}
@@ -0,0 +1,264 @@
+use super::average::Average;
+use std::collections::VecDeque;
+use std::marker::PhantomData;
+use std::ops::{Add, Sub};
+
+/// Sliding windows of a fixed size over a sequence.
+///
+/// The windows are kept in one reused buffer, so each window is borrowed as a slice and
+/// must be released before advancing to the next one.
+#[derive(Clone)]
+pub struct SlidingWindow<I: Iterator> {
+    source: I,
+    size: usize,
+    seen: usize,
+    buffer: Vec<I::Item>,
+}
+
+impl<I> SlidingWindow<I>
+where
+    I: Iterator,
+    I::Item: Clone,
+{
+    /// Advances to the next window and returns it, or `None` if the sequence is exhausted.
+    pub fn next_window(&mut self) -> Option<&[I::Item]> {
+        while self.seen < self.size {
+            let item = self.source.next()?;
+            self.buffer.push(item);
+            self.seen += 1;
+            if self.seen == self.size {
+                // Store every element twice, so that the current window is always contiguous.
+                self.buffer.extend_from_within(..);
+                return Some(&self.buffer[..self.size]);
+            }
+        }
+        let item = self.source.next()?;
+        let position = self.seen % self.size;
+        self.buffer[position] = item.clone();
+        self.buffer[position + self.size] = item;
+        self.seen += 1;
+        let start = self.seen % self.size;
+        Some(&self.buffer[start..start + self.size])
+    }
+
+    /// Projects each window into a new form.
+    pub fn select<TResult, F>(self, f: F) -> WindowSelectIterator<I, F>
+    where
+        F: FnMut(&[I::Item]) -> TResult,
+    {
+        WindowSelectIterator {
+            windows: self,
+            func: f,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct WindowSelectIterator<I: Iterator, F>
+where
+    I::Item: Clone,
+{
+    windows: SlidingWindow<I>,
+    func: F,
+}
+
+impl<I, F, R> Iterator for WindowSelectIterator<I, F>
+where
+    I: Iterator,
+    I::Item: Clone,
+    F: FnMut(&[I::Item]) -> R,
+{
+    type Item = R;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let window = self.windows.next_window()?;
+        Some((self.func)(window))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let windows = &self.windows;
+        window_size_hint(windows.source.size_hint(), windows.size, windows.seen)
+    }
+}
+
+pub fn window<I>(iter: I, size: usize) -> SlidingWindow<I>
+where
+    I: Iterator,
+    I::Item: Clone,
+{
+    assert!(size > 0, "window size must be positive");
+    SlidingWindow {
+        buffer: Vec::with_capacity(capacity(size, &iter).saturating_mul(2)),
+        source: iter,
+        size,
+        seen: 0,
+    }
+}
+
+#[derive(Clone)]
+pub struct MovingSumIterator<I: Iterator> {
+    source: I,
+    size: usize,
+    window: VecDeque<I::Item>,
+    sum: I::Item,
+}
+
+impl<I> Iterator for MovingSumIterator<I>
+where
+    I: Iterator,
+    I::Item: Copy + Default + Add<Output = I::Item> + Sub<Output = I::Item>,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let item = self.source.next()?;
+            if self.window.len() == self.size {
+                let removed = self.window.pop_front().unwrap();
+                self.sum = self.sum - removed;
+            }
+            self.window.push_back(item);
+            self.sum = self.sum + item;
+            if self.window.len() == self.size {
+                return Some(self.sum);
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        window_size_hint(self.source.size_hint(), self.size, self.window.len())
+    }
+}
+
+pub fn moving_sum<I>(iter: I, size: usize) -> MovingSumIterator<I>
+where
+    I: Iterator,
+    I::Item: Copy + Default + Add<Output = I::Item> + Sub<Output = I::Item>,
+{
+    assert!(size > 0, "window size must be positive");
+    MovingSumIterator {
+        window: VecDeque::with_capacity(capacity(size, &iter)),
+        source: iter,
+        size,
+        sum: Default::default(),
+    }
+}
+
+#[derive(Clone)]
+pub struct MovingAverageIterator<I: Iterator, A>
+where
+    I::Item: Clone,
+{
+    sums: MovingSumIterator<I>,
+    average: PhantomData<A>,
+}
+
+impl<I, A> Iterator for MovingAverageIterator<I, A>
+where
+    I: Iterator,
+    I::Item: Copy + Default + Add<Output = I::Item> + Sub<Output = I::Item>,
+    A: Average<I::Item>,
+{
+    type Item = A;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let sum = self.sums.next()?;
+        Some(A::from_sum(sum, self.sums.size))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.sums.size_hint()
+    }
+}
+
+pub fn moving_average<I, A>(iter: I, size: usize) -> MovingAverageIterator<I, A>
+where
+    I: Iterator,
+    I::Item: Copy + Default + Add<Output = I::Item> + Sub<Output = I::Item>,
+    A: Average<I::Item>,
+{
+    MovingAverageIterator {
+        sums: moving_sum(iter, size),
+        average: PhantomData,
+    }
+}
+
+/// Yields the minimum or maximum of each window, keeping candidates in a monotonic deque.
+#[derive(Clone)]
+pub struct MovingExtremumIterator<I: Iterator> {
+    source: I,
+    size: usize,
+    seen: usize,
+    maximum: bool,
+    candidates: VecDeque<(usize, I::Item)>,
+}
+
+impl<I> Iterator for MovingExtremumIterator<I>
+where
+    I: Iterator,
+    I::Item: Ord + Clone,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let item = self.source.next()?;
+            let index = self.seen;
+            self.seen += 1;
+            while let Some((_, last)) = self.candidates.back() {
+                let dominated = if self.maximum {
+                    last <= &item
+                } else {
+                    last >= &item
+                };
+                if !dominated {
+                    break;
+                }
+                self.candidates.pop_back();
+            }
+            self.candidates.push_back((index, item));
+            if index - self.candidates[0].0 >= self.size {
+                self.candidates.pop_front();
+            }
+            if self.seen >= self.size {
+                return Some(self.candidates[0].1.clone());
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        window_size_hint(self.source.size_hint(), self.size, self.seen)
+    }
+}
+
+pub fn moving_extremum<I>(iter: I, size: usize, maximum: bool) -> MovingExtremumIterator<I>
+where
+    I: Iterator,
+    I::Item: Ord + Clone,
+{
+    assert!(size > 0, "window size must be positive");
+    MovingExtremumIterator {
+        candidates: VecDeque::with_capacity(capacity(size, &iter)),
+        source: iter,
+        size,
+        seen: 0,
+        maximum,
+    }
+}
+
+/// A window never holds more elements than the source has, so a large `size` doesn't allocate up front.
+fn capacity<I: Iterator>(size: usize, iter: &I) -> usize {
+    size.min(iter.size_hint().0)
+}
+
+fn window_size_hint(
+    (lower, upper): (usize, Option<usize>),
+    size: usize,
+    seen: usize,
+) -> (usize, Option<usize>) {
+    let pending = (size - 1).saturating_sub(seen);
+    (
+        lower.saturating_sub(pending),
+        upper.map(|x| x.saturating_sub(pending)),
+    )
+}
@@ -0,0 +1,481 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+/// An input of a sorted operation was not in ascending key order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct UnsortedError {
+    /// Which input was unsorted: 0 for the sequence the operation was called on, 1 for the other one.
+    pub source: usize,
+    /// The position in that input of the first element whose key is less than the key before it.
+    pub position: usize,
+}
+
+impl fmt::Display for UnsortedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "input {} is not sorted at position {}",
+            self.source, self.position
+        )
+    }
+}
+
+impl std::error::Error for UnsortedError {}
+
+/// One sorted input, remembering the key of the element taken last to detect runs of equal keys.
+struct SortedSource<I: Iterator, K> {
+    iter: std::iter::Fuse<I>,
+    head: Option<(K, I::Item)>,
+    last: Option<K>,
+    position: usize,
+    validate: bool,
+    unsorted: Option<usize>,
+}
+
+// `derive` wouldn't require the buffered items to be `Clone`.
+impl<I, K> Clone for SortedSource<I, K>
+where
+    I: Iterator + Clone,
+    I::Item: Clone,
+    K: Clone,
+{
+    fn clone(&self) -> Self {
+        SortedSource {
+            iter: self.iter.clone(),
+            head: self.head.clone(),
+            last: self.last.clone(),
+            position: self.position,
+            validate: self.validate,
+            unsorted: self.unsorted,
+        }
+    }
+}
+
+impl<I, K> SortedSource<I, K>
+where
+    I: Iterator,
+    K: Ord,
+{
+    fn new(iter: I) -> Self {
+        SortedSource {
+            iter: iter.fuse(),
+            head: None,
+            last: None,
+            position: 0,
+            validate: false,
+            unsorted: None,
+        }
+    }
+
+    fn peek<F: Fn(&I::Item) -> K>(&mut self, key: &F) -> Option<&K> {
+        if self.head.is_none() {
+            let item = self.iter.next()?;
+            let k = key(&item);
+            if self.validate && self.unsorted.is_none() {
+                if let Some(last) = &self.last {
+                    if k < *last {
+                        self.unsorted = Some(self.position);
+                    }
+                }
+            }
+            self.position += 1;
+            self.head = Some((k, item));
+        }
+        self.head.as_ref().map(|(k, _)| k)
+    }
+
+    fn take<F: Fn(&I::Item) -> K>(&mut self, key: &F) -> Option<I::Item> {
+        self.peek(key)?;
+        let (k, item) = self.head.take()?;
+        self.last = Some(k);
+        Some(item)
+    }
+
+    /// Skips the following elements whose key equals `k`.
+    fn skip_equal<F: Fn(&I::Item) -> K>(&mut self, key: &F, k: &K) {
+        while self.peek(key) == Some(k) {
+            self.take(key);
+        }
+    }
+
+    /// Checks whether the next element has the same key as the element taken last.
+    fn next_equals_last<F: Fn(&I::Item) -> K>(&mut self, key: &F) -> bool {
+        self.peek(key).is_some() && self.head.as_ref().map(|(k, _)| k) == self.last.as_ref()
+    }
+
+    /// Skips the following elements whose key equals the key of the element taken last.
+    fn skip_equal_to_last<F: Fn(&I::Item) -> K>(&mut self, key: &F) {
+        while self.next_equals_last(key) {
+            self.head = None;
+        }
+    }
+
+    fn error(&self, source: usize) -> Option<UnsortedError> {
+        self.unsorted
+            .map(|position| UnsortedError { source, position })
+    }
+}
+
+/// An operation over sorted inputs which can check that the inputs really are sorted.
+///
+/// It's implemented by the results of `sorted_distinct`, the sorted set operations and `merge_join`.
+pub trait SortedOperation: Iterator {
+    fn enable_validation(&mut self);
+
+    fn error(&self) -> Option<UnsortedError>;
+
+    /// Checks that the inputs are sorted while iterating, yielding an `UnsortedError` instead of
+    /// continuing once they are not.
+    fn validated(mut self) -> ValidatedIterator<Self>
+    where
+        Self: Sized,
+    {
+        self.enable_validation();
+        ValidatedIterator {
+            operation: self,
+            failed: false,
+        }
+    }
+}
+
+/// Yields the results of a sorted operation, or an error once an input is found to be unsorted.
+#[derive(Clone)]
+pub struct ValidatedIterator<S> {
+    operation: S,
+    failed: bool,
+}
+
+impl<S: SortedOperation> Iterator for ValidatedIterator<S> {
+    type Item = Result<S::Item, UnsortedError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        let item = self.operation.next();
+        if let Some(error) = self.operation.error() {
+            self.failed = true;
+            return Some(Err(error));
+        }
+        item.map(Ok)
+    }
+}
+
+pub struct SortedDistinctIterator<I: Iterator, K, F> {
+    source: SortedSource<I, K>,
+    key: F,
+}
+
+impl<I, K, F> Clone for SortedDistinctIterator<I, K, F>
+where
+    I: Iterator + Clone,
+    I::Item: Clone,
+    K: Clone,
+    F: Clone,
+{
+    fn clone(&self) -> Self {
+        SortedDistinctIterator {
+            source: self.source.clone(),
+            key: self.key.clone(),
+        }
+    }
+}
+
+impl<I, K, F> Iterator for SortedDistinctIterator<I, K, F>
+where
+    I: Iterator,
+    K: Ord,
+    F: Fn(&I::Item) -> K,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.source.take(&self.key)?;
+        self.source.skip_equal_to_last(&self.key);
+        Some(item)
+    }
+}
+
+impl<I, K, F> SortedOperation for SortedDistinctIterator<I, K, F>
+where
+    I: Iterator,
+    K: Ord,
+    F: Fn(&I::Item) -> K,
+{
+    fn enable_validation(&mut self) {
+        self.source.validate = true;
+    }
+
+    fn error(&self) -> Option<UnsortedError> {
+        self.source.error(0)
+    }
+}
+
+pub fn sorted_distinct<I, K, F>(iter: I, key: F) -> SortedDistinctIterator<I, K, F>
+where
+    I: Iterator,
+    K: Ord,
+    F: Fn(&I::Item) -> K,
+{
+    SortedDistinctIterator {
+        source: SortedSource::new(iter),
+        key,
+    }
+}
+
+/// Which set operation a `SortedSetIterator` performs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SetOperation {
+    Union,
+    Intersect,
+    Except,
+}
+
+pub struct SortedSetIterator<I: Iterator, U: Iterator, K, F> {
+    first: SortedSource<I, K>,
+    second: SortedSource<U, K>,
+    key: F,
+    operation: SetOperation,
+}
+
+impl<I, U, K, F> Clone for SortedSetIterator<I, U, K, F>
+where
+    I: Iterator + Clone,
+    U: Iterator<Item = I::Item> + Clone,
+    I::Item: Clone,
+    K: Clone,
+    F: Clone,
+{
+    fn clone(&self) -> Self {
+        SortedSetIterator {
+            first: self.first.clone(),
+            second: self.second.clone(),
+            key: self.key.clone(),
+            operation: self.operation,
+        }
+    }
+}
+
+impl<I, U, K, F> Iterator for SortedSetIterator<I, U, K, F>
+where
+    I: Iterator,
+    U: Iterator<Item = I::Item>,
+    K: Ord,
+    F: Fn(&I::Item) -> K,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let key = &self.key;
+        loop {
+            let order = match (self.first.peek(key), self.second.peek(key)) {
+                (Some(a), Some(b)) => a.cmp(b),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => return None,
+            };
+            match order {
+                Ordering::Less => {
+                    let item = self.first.take(key);
+                    self.first.skip_equal_to_last(key);
+                    if self.operation != SetOperation::Intersect {
+                        return item;
+                    }
+                    // Nothing else can intersect once the other input is exhausted.
+                    self.second.peek(key)?;
+                }
+                Ordering::Greater => {
+                    if self.operation != SetOperation::Union && self.first.peek(key).is_none() {
+                        return None;
+                    }
+                    let item = self.second.take(key);
+                    self.second.skip_equal_to_last(key);
+                    if self.operation == SetOperation::Union {
+                        return item;
+                    }
+                }
+                Ordering::Equal => {
+                    let item = self.first.take(key);
+                    self.first.skip_equal_to_last(key);
+                    if let Some(last) = &self.first.last {
+                        self.second.skip_equal(key, last);
+                    }
+                    if self.operation != SetOperation::Except {
+                        return item;
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<I, U, K, F> SortedOperation for SortedSetIterator<I, U, K, F>
+where
+    I: Iterator,
+    U: Iterator<Item = I::Item>,
+    K: Ord,
+    F: Fn(&I::Item) -> K,
+{
+    fn enable_validation(&mut self) {
+        self.first.validate = true;
+        self.second.validate = true;
+    }
+
+    fn error(&self) -> Option<UnsortedError> {
+        self.first.error(0).or_else(|| self.second.error(1))
+    }
+}
+
+pub fn sorted_set<I, U, K, F>(
+    iter: I,
+    other: U,
+    key: F,
+    operation: SetOperation,
+) -> SortedSetIterator<I, U, K, F>
+where
+    I: Iterator,
+    U: Iterator<Item = I::Item>,
+    K: Ord,
+    F: Fn(&I::Item) -> K,
+{
+    SortedSetIterator {
+        first: SortedSource::new(iter),
+        second: SortedSource::new(other),
+        key,
+        operation,
+    }
+}
+
+/// Joins two inputs sorted by key, buffering only the current run of equal keys of the second input.
+pub struct MergeJoinIterator<I: Iterator, U: Iterator, K, FI, FU, FR> {
+    first: SortedSource<I, K>,
+    second: SortedSource<U, K>,
+    first_key: FI,
+    second_key: FU,
+    result: FR,
+    current: Option<I::Item>,
+    run: Vec<U::Item>,
+    index: usize,
+}
+
+impl<I, U, K, FI, FU, FR> Clone for MergeJoinIterator<I, U, K, FI, FU, FR>
+where
+    I: Iterator + Clone,
+    U: Iterator + Clone,
+    I::Item: Clone,
+    U::Item: Clone,
+    K: Clone,
+    FI: Clone,
+    FU: Clone,
+    FR: Clone,
+{
+    fn clone(&self) -> Self {
+        MergeJoinIterator {
+            first: self.first.clone(),
+            second: self.second.clone(),
+            first_key: self.first_key.clone(),
+            second_key: self.second_key.clone(),
+            result: self.result.clone(),
+            current: self.current.clone(),
+            run: self.run.clone(),
+            index: self.index,
+        }
+    }
+}
+
+impl<I, U, K, FI, FU, FR, R> Iterator for MergeJoinIterator<I, U, K, FI, FU, FR>
+where
+    I: Iterator,
+    U: Iterator,
+    K: Ord,
+    FI: Fn(&I::Item) -> K,
+    FU: Fn(&U::Item) -> K,
+    FR: FnMut(&I::Item, &U::Item) -> R,
+{
+    type Item = R;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(current) = &self.current {
+                if self.index < self.run.len() {
+                    self.index += 1;
+                    return Some((self.result)(current, &self.run[self.index - 1]));
+                }
+                self.current = None;
+            }
+
+            let first_key = self.first.peek(&self.first_key)?;
+            if !self.run.is_empty() && self.second.last.as_ref() == Some(first_key) {
+                self.current = self.first.take(&self.first_key);
+                self.index = 0;
+                continue;
+            }
+
+            self.run.clear();
+            let first_key = self.first.peek(&self.first_key)?;
+            match self.second.peek(&self.second_key) {
+                None => return None,
+                Some(second_key) => match second_key.cmp(first_key) {
+                    Ordering::Less => {
+                        self.second.take(&self.second_key);
+                    }
+                    Ordering::Greater => {
+                        self.first.take(&self.first_key);
+                    }
+                    Ordering::Equal => {
+                        self.run.extend(self.second.take(&self.second_key));
+                        while self.second.next_equals_last(&self.second_key) {
+                            self.run.extend(self.second.take(&self.second_key));
+                        }
+                    }
+                },
+            }
+        }
+    }
+}
+
+impl<I, U, K, FI, FU, FR, R> SortedOperation for MergeJoinIterator<I, U, K, FI, FU, FR>
+where
+    I: Iterator,
+    U: Iterator,
+    K: Ord,
+    FI: Fn(&I::Item) -> K,
+    FU: Fn(&U::Item) -> K,
+    FR: FnMut(&I::Item, &U::Item) -> R,
+{
+    fn enable_validation(&mut self) {
+        self.first.validate = true;
+        self.second.validate = true;
+    }
+
+    fn error(&self) -> Option<UnsortedError> {
+        self.first.error(0).or_else(|| self.second.error(1))
+    }
+}
+
+pub fn merge_join<I, U, K, FI, FU, FR, R>(
+    iter: I,
+    other: U,
+    first_key: FI,
+    second_key: FU,
+    result: FR,
+) -> MergeJoinIterator<I, U, K, FI, FU, FR>
+where
+    I: Iterator,
+    U: Iterator,
+    K: Ord,
+    FI: Fn(&I::Item) -> K,
+    FU: Fn(&U::Item) -> K,
+    FR: FnMut(&I::Item, &U::Item) -> R,
+{
+    MergeJoinIterator {
+        first: SortedSource::new(iter),
+        second: SortedSource::new(other),
+        first_key,
+        second_key,
+        result,
+        current: None,
+        run: Vec::new(),
+        index: 0,
+    }
+}
@@ -0,0 +1,260 @@
+use super::m_order_by;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// How a percentile between two data points is computed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Interpolation {
+    /// Interpolates linearly between the two data points.
+    Linear,
+    /// Takes the lower data point.
+    Lower,
+    /// Takes the higher data point.
+    Higher,
+    /// Takes the nearest data point, rounding halves away from the lower one.
+    Nearest,
+    /// Takes the mean of the two data points.
+    Midpoint,
+}
+
+/// Whether a variance describes a whole population or a sample of it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum VarianceKind {
+    /// Divides by `n`.
+    Population,
+    /// Divides by `n - 1` (Bessel's correction).
+    Sample,
+}
+
+pub fn percentile<I, F>(iter: I, p: f64, interpolation: Interpolation, f: F) -> Option<f64>
+where
+    I: Iterator,
+    F: Fn(&I::Item) -> f64,
+{
+    assert!(
+        (0.0..=100.0).contains(&p),
+        "percentile must be between 0 and 100"
+    );
+    // NaN has no rank, so it's skipped like in `histogram`.
+    let mut values: Vec<f64> = iter.map(|item| f(&item)).filter(|v| !v.is_nan()).collect();
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_by(|a, b| a.total_cmp(b));
+    Some(percentile_of_sorted(&values, p, interpolation))
+}
+
+/// Computes the `p`-th percentile of a non-empty, ascending slice.
+pub fn percentile_of_sorted(values: &[f64], p: f64, interpolation: Interpolation) -> f64 {
+    let rank = p / 100.0 * (values.len() - 1) as f64;
+    let lower = values[rank.floor() as usize];
+    let higher = values[rank.ceil() as usize];
+    let fraction = rank - rank.floor();
+    match interpolation {
+        Interpolation::Linear if (higher - lower).is_finite() => {
+            lower + (higher - lower) * fraction
+        }
+        Interpolation::Linear => lower * (1.0 - fraction) + higher * fraction,
+        Interpolation::Lower => lower,
+        Interpolation::Higher => higher,
+        Interpolation::Nearest => {
+            if fraction < 0.5 {
+                lower
+            } else {
+                higher
+            }
+        }
+        Interpolation::Midpoint => (lower + higher) / 2.0,
+    }
+}
+
+pub fn mode<I, K, F>(iter: I, f: F) -> Option<K>
+where
+    I: Iterator,
+    K: Eq + Hash,
+    F: Fn(&I::Item) -> K,
+{
+    let mut counts: HashMap<K, (usize, usize)> = HashMap::new();
+    for (index, item) in iter.enumerate() {
+        counts.entry(f(&item)).or_insert((0, index)).0 += 1;
+    }
+    // Ties are broken in favour of the key that appears first.
+    counts
+        .into_iter()
+        .max_by(|(_, a), (_, b)| a.0.cmp(&b.0).then(b.1.cmp(&a.1)))
+        .map(|(key, _)| key)
+}
+
+pub fn variance<I, F>(iter: I, kind: VarianceKind, f: F) -> Option<f64>
+where
+    I: Iterator,
+    F: Fn(&I::Item) -> f64,
+{
+    // Welford's online algorithm.
+    let mut count = 0usize;
+    let mut mean = 0.0;
+    let mut m2 = 0.0;
+    for item in iter {
+        let value = f(&item);
+        count += 1;
+        let delta = value - mean;
+        mean += delta / count as f64;
+        m2 += delta * (value - mean);
+    }
+    match kind {
+        VarianceKind::Population if count > 0 => Some(m2 / count as f64),
+        VarianceKind::Sample if count > 1 => Some(m2 / (count - 1) as f64),
+        _ => None,
+    }
+}
+
+pub fn min_max<I, K, F>(mut iter: I, f: F) -> Option<(I::Item, I::Item)>
+where
+    I: Iterator,
+    I::Item: Clone,
+    K: Ord,
+    F: Fn(&I::Item) -> K,
+{
+    let first = iter.next()?;
+    let first = (f(&first), first);
+    // `None` stands for the first element, so its key is computed once and never cloned.
+    let mut min: Option<(K, I::Item)> = None;
+    let mut max: Option<(K, I::Item)> = None;
+    for item in iter {
+        let key = f(&item);
+        if key < min.as_ref().unwrap_or(&first).0 {
+            min = Some((key, item));
+        } else if key >= max.as_ref().unwrap_or(&first).0 {
+            max = Some((key, item));
+        }
+    }
+    let min = min.map_or_else(|| first.1.clone(), |(_, item)| item);
+    let max = max.map_or(first.1, |(_, item)| item);
+    Some((min, max))
+}
+
+/// The least-squares line `y = slope * x + intercept` fitted to a set of points.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LinearRegression {
+    pub slope: f64,
+    pub intercept: f64,
+    /// The coefficient of determination.
+    pub r_squared: f64,
+}
+
+/// Running means and co-moments of pairs of values, updated with Welford's algorithm.
+#[derive(Default)]
+struct CoMoments {
+    count: usize,
+    mean_x: f64,
+    mean_y: f64,
+    m2_x: f64,
+    m2_y: f64,
+    c_xy: f64,
+}
+
+impl CoMoments {
+    fn from_pairs<I: Iterator<Item = (f64, f64)>>(iter: I) -> Self {
+        let mut moments = CoMoments::default();
+        for (x, y) in iter {
+            moments.count += 1;
+            let n = moments.count as f64;
+            let dx = x - moments.mean_x;
+            let dy = y - moments.mean_y;
+            moments.mean_x += dx / n;
+            moments.mean_y += dy / n;
+            moments.m2_x += dx * (x - moments.mean_x);
+            moments.m2_y += dy * (y - moments.mean_y);
+            moments.c_xy += dx * (y - moments.mean_y);
+        }
+        moments
+    }
+
+    fn pearson(&self) -> Option<f64> {
+        if self.count < 2 || self.m2_x == 0.0 || self.m2_y == 0.0 {
+            return None;
+        }
+        Some(self.c_xy / (self.m2_x * self.m2_y).sqrt())
+    }
+}
+
+pub fn covariance<I, FX, FY>(iter: I, kind: VarianceKind, x: FX, y: FY) -> Option<f64>
+where
+    I: Iterator,
+    FX: Fn(&I::Item) -> f64,
+    FY: Fn(&I::Item) -> f64,
+{
+    let moments = CoMoments::from_pairs(iter.map(|item| (x(&item), y(&item))));
+    match kind {
+        VarianceKind::Population if moments.count > 0 => Some(moments.c_xy / moments.count as f64),
+        VarianceKind::Sample if moments.count > 1 => {
+            Some(moments.c_xy / (moments.count - 1) as f64)
+        }
+        _ => None,
+    }
+}
+
+pub fn correlation<I, FX, FY>(iter: I, x: FX, y: FY) -> Option<f64>
+where
+    I: Iterator,
+    FX: Fn(&I::Item) -> f64,
+    FY: Fn(&I::Item) -> f64,
+{
+    CoMoments::from_pairs(iter.map(|item| (x(&item), y(&item)))).pearson()
+}
+
+pub fn spearman_correlation<I, KX, KY, FX, FY>(iter: I, x: FX, y: FY) -> Option<f64>
+where
+    I: Iterator,
+    KX: Ord,
+    KY: Ord,
+    FX: Fn(&I::Item) -> KX,
+    FY: Fn(&I::Item) -> KY,
+{
+    let (xs, ys): (Vec<KX>, Vec<KY>) = iter.map(|item| (x(&item), y(&item))).unzip();
+    let x_ranks = fractional_ranks(&xs);
+    let y_ranks = fractional_ranks(&ys);
+    CoMoments::from_pairs(x_ranks.into_iter().zip(y_ranks)).pearson()
+}
+
+pub fn linear_regression<I, FX, FY>(iter: I, x: FX, y: FY) -> Option<LinearRegression>
+where
+    I: Iterator,
+    FX: Fn(&I::Item) -> f64,
+    FY: Fn(&I::Item) -> f64,
+{
+    let moments = CoMoments::from_pairs(iter.map(|item| (x(&item), y(&item))));
+    if moments.count < 2 || moments.m2_x == 0.0 {
+        return None;
+    }
+    let slope = moments.c_xy / moments.m2_x;
+    let r_squared = if moments.m2_y == 0.0 {
+        1.0
+    } else {
+        moments.c_xy * moments.c_xy / (moments.m2_x * moments.m2_y)
+    };
+    Some(LinearRegression {
+        slope,
+        intercept: moments.mean_y - slope * moments.mean_x,
+        r_squared,
+    })
+}
+
+/// Ranks the keys from 1, giving tied keys the average of the ranks they span.
+fn fractional_ranks<K: Ord>(keys: &[K]) -> Vec<f64> {
+    let order: Vec<usize> = m_order_by::order_by(0..keys.len(), |i| &keys[*i], false).collect();
+    let mut ranks = vec![0.0; keys.len()];
+    let mut start = 0;
+    while start < order.len() {
+        let mut end = start + 1;
+        while end < order.len() && keys[order[end]] == keys[order[start]] {
+            end += 1;
+        }
+        let rank = (start + end + 1) as f64 / 2.0;
+        for index in &order[start..end] {
+            ranks[*index] = rank;
+        }
+        start = end;
+    }
+    ranks
+}
@@ -0,0 +1,135 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::fmt;
+use std::hash::Hash;
+
+/// The reason why items could not be sorted topologically.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TopologicalSortError<K> {
+    /// Several items have the same id.
+    DuplicateId(K),
+    /// An item depends on an id which does not exist, as `(id, dependency)`.
+    MissingDependency(K, K),
+    /// The ids of the items which depend on each other in a cycle, in source order.
+    Cycle(Vec<K>),
+}
+
+impl<K: fmt::Debug> fmt::Display for TopologicalSortError<K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TopologicalSortError::DuplicateId(id) => write!(f, "duplicate id {:?}", id),
+            TopologicalSortError::MissingDependency(id, dependency) => {
+                write!(f, "{:?} depends on missing {:?}", id, dependency)
+            }
+            TopologicalSortError::Cycle(ids) => write!(f, "cycle between {:?}", ids),
+        }
+    }
+}
+
+impl<K: fmt::Debug> std::error::Error for TopologicalSortError<K> {}
+
+pub type TopologicalSortIterator<T> = std::vec::IntoIter<T>;
+
+pub fn topological_sort<I, K, C, FI, FD>(
+    iter: I,
+    id: FI,
+    dependencies: FD,
+) -> Result<TopologicalSortIterator<I::Item>, TopologicalSortError<K>>
+where
+    I: Iterator,
+    K: Eq + Hash + Clone,
+    C: IntoIterator<Item = K>,
+    FI: Fn(&I::Item) -> K,
+    FD: Fn(&I::Item) -> C,
+{
+    let items: Vec<_> = iter.collect();
+    let ids: Vec<K> = items.iter().map(&id).collect();
+
+    let mut indices = HashMap::with_capacity(ids.len());
+    for (index, id) in ids.iter().enumerate() {
+        if indices.insert(id.clone(), index).is_some() {
+            return Err(TopologicalSortError::DuplicateId(id.clone()));
+        }
+    }
+
+    let mut dependents = vec![Vec::new(); items.len()];
+    let mut pending = vec![0usize; items.len()];
+    for (index, item) in items.iter().enumerate() {
+        for dependency in dependencies(item) {
+            match indices.get(&dependency) {
+                Some(dependency) => {
+                    dependents[*dependency].push(index);
+                    pending[index] += 1;
+                }
+                None => {
+                    return Err(TopologicalSortError::MissingDependency(
+                        ids[index].clone(),
+                        dependency,
+                    ))
+                }
+            }
+        }
+    }
+
+    // Kahn's algorithm, always taking the earliest ready item in source order.
+    let mut ready: BinaryHeap<Reverse<usize>> = (0..items.len())
+        .filter(|index| pending[*index] == 0)
+        .map(Reverse)
+        .collect();
+    let mut order = Vec::with_capacity(items.len());
+    while let Some(Reverse(index)) = ready.pop() {
+        order.push(index);
+        for dependent in &dependents[index] {
+            pending[*dependent] -= 1;
+            if pending[*dependent] == 0 {
+                ready.push(Reverse(*dependent));
+            }
+        }
+    }
+
+    if order.len() < items.len() {
+        return Err(TopologicalSortError::Cycle(find_cycle(
+            &dependents,
+            &pending,
+            &ids,
+        )));
+    }
+
+    let mut slots: Vec<Option<I::Item>> = items.into_iter().map(Some).collect();
+    let sorted: Vec<_> = order
+        .into_iter()
+        .map(|index| slots[index].take().unwrap())
+        .collect();
+    Ok(sorted.into_iter())
+}
+
+/// Finds the items on one cycle among the items which were never ready.
+fn find_cycle<K: Clone>(dependents: &[Vec<usize>], pending: &[usize], ids: &[K]) -> Vec<K> {
+    // Every blocked item has a blocked dependency, so walking backwards along them must revisit an item.
+    let mut blocking = vec![None; pending.len()];
+    for (index, targets) in dependents.iter().enumerate() {
+        if pending[index] > 0 {
+            for target in targets {
+                if pending[*target] > 0 {
+                    blocking[*target] = Some(index);
+                }
+            }
+        }
+    }
+
+    let mut visited = vec![false; pending.len()];
+    let mut index = pending.iter().position(|count| *count > 0).unwrap();
+    while !visited[index] {
+        visited[index] = true;
+        index = blocking[index].unwrap();
+    }
+    let start = index;
+    let mut cycle = vec![start];
+    index = blocking[start].unwrap();
+    while index != start {
+        cycle.push(index);
+        index = blocking[index].unwrap();
+    }
+    cycle.sort_unstable();
+    cycle.into_iter().map(|index| ids[index].clone()).collect()
+}
@@ -0,0 +1,220 @@
+use std::collections::{HashSet, VecDeque};
+use std::hash::Hash;
+
+/// Decides whether an element reached during a traversal is yielded and expanded.
+pub trait Visitor<T> {
+    fn visit(&mut self, item: &T) -> bool;
+}
+
+/// Visits every element, even if it was reached before.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct VisitAll;
+
+impl<T> Visitor<T> for VisitAll {
+    fn visit(&mut self, _item: &T) -> bool {
+        true
+    }
+}
+
+/// Visits only the first element reached with each id.
+#[derive(Clone)]
+pub struct VisitOnce<K, F> {
+    seen: HashSet<K>,
+    id: F,
+}
+
+impl<T, K, F> Visitor<T> for VisitOnce<K, F>
+where
+    K: Eq + Hash,
+    F: FnMut(&T) -> K,
+{
+    fn visit(&mut self, item: &T) -> bool {
+        self.seen.insert((self.id)(item))
+    }
+}
+
+/// Recursively expands every element of the source into its children, in depth-first (pre-order) or
+/// breadth-first order.
+pub struct TraverseIterator<I, F, C, V = VisitAll>
+where
+    C: IntoIterator,
+{
+    roots: std::iter::Fuse<I>,
+    children: F,
+    pending: VecDeque<(usize, C::IntoIter)>,
+    breadth_first: bool,
+    visitor: V,
+}
+
+impl<I, F, C, V> TraverseIterator<I, F, C, V>
+where
+    I: Iterator,
+    F: FnMut(&I::Item) -> C,
+    C: IntoIterator<Item = I::Item>,
+    V: Visitor<I::Item>,
+{
+    /// Yields each element paired with its depth, where the source elements have depth 0.
+    pub fn with_depth(self) -> TraverseWithDepthIterator<I, F, C, V> {
+        TraverseWithDepthIterator { traversal: self }
+    }
+
+    /// Skips elements whose id was already reached, together with their children,
+    /// so that cyclic graphs are traversed only once.
+    pub fn distinct_by<K, FK>(self, id: FK) -> TraverseIterator<I, F, C, VisitOnce<K, FK>>
+    where
+        K: Eq + Hash,
+        FK: FnMut(&I::Item) -> K,
+    {
+        TraverseIterator {
+            roots: self.roots,
+            children: self.children,
+            pending: self.pending,
+            breadth_first: self.breadth_first,
+            visitor: VisitOnce {
+                seen: HashSet::new(),
+                id,
+            },
+        }
+    }
+
+    fn next_with_depth(&mut self) -> Option<(usize, I::Item)> {
+        loop {
+            let next = if self.breadth_first {
+                // All source elements form the first level.
+                match self.roots.next() {
+                    Some(root) => Some((0, root)),
+                    None => {
+                        let (depth, children) = self.pending.front_mut()?;
+                        children.next().map(|child| (*depth, child))
+                    }
+                }
+            } else {
+                match self.pending.back_mut() {
+                    Some((depth, children)) => children.next().map(|child| (*depth, child)),
+                    None => Some((0, self.roots.next()?)),
+                }
+            };
+
+            let (depth, item) = match next {
+                Some(next) => next,
+                None => {
+                    if self.breadth_first {
+                        self.pending.pop_front();
+                    } else {
+                        self.pending.pop_back();
+                    }
+                    continue;
+                }
+            };
+            if !self.visitor.visit(&item) {
+                continue;
+            }
+            let children = (self.children)(&item).into_iter();
+            self.pending.push_back((depth + 1, children));
+            return Some((depth, item));
+        }
+    }
+}
+
+// `derive` wouldn't require the child iterators to be `Clone`.
+impl<I, F, C, V> Clone for TraverseIterator<I, F, C, V>
+where
+    I: Clone,
+    F: Clone,
+    C: IntoIterator,
+    C::IntoIter: Clone,
+    V: Clone,
+{
+    fn clone(&self) -> Self {
+        TraverseIterator {
+            roots: self.roots.clone(),
+            children: self.children.clone(),
+            pending: self.pending.clone(),
+            breadth_first: self.breadth_first,
+            visitor: self.visitor.clone(),
+        }
+    }
+}
+
+impl<I, F, C, V> Iterator for TraverseIterator<I, F, C, V>
+where
+    I: Iterator,
+    F: FnMut(&I::Item) -> C,
+    C: IntoIterator<Item = I::Item>,
+    V: Visitor<I::Item>,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_with_depth().map(|(_, item)| item)
+    }
+}
+
+impl<I, F, C, V> std::iter::FusedIterator for TraverseIterator<I, F, C, V>
+where
+    I: Iterator,
+    F: FnMut(&I::Item) -> C,
+    C: IntoIterator<Item = I::Item>,
+    V: Visitor<I::Item>,
+{
+}
+
+pub struct TraverseWithDepthIterator<I, F, C, V>
+where
+    C: IntoIterator,
+{
+    traversal: TraverseIterator<I, F, C, V>,
+}
+
+impl<I, F, C, V> Clone for TraverseWithDepthIterator<I, F, C, V>
+where
+    I: Clone,
+    F: Clone,
+    C: IntoIterator,
+    C::IntoIter: Clone,
+    V: Clone,
+{
+    fn clone(&self) -> Self {
+        TraverseWithDepthIterator {
+            traversal: self.traversal.clone(),
+        }
+    }
+}
+
+impl<I, F, C, V> Iterator for TraverseWithDepthIterator<I, F, C, V>
+where
+    I: Iterator,
+    F: FnMut(&I::Item) -> C,
+    C: IntoIterator<Item = I::Item>,
+    V: Visitor<I::Item>,
+{
+    type Item = (usize, I::Item);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.traversal.next_with_depth()
+    }
+}
+
+impl<I, F, C, V> std::iter::FusedIterator for TraverseWithDepthIterator<I, F, C, V>
+where
+    I: Iterator,
+    F: FnMut(&I::Item) -> C,
+    C: IntoIterator<Item = I::Item>,
+    V: Visitor<I::Item>,
+{
+}
+
+pub fn traverse<I, F, C>(iter: I, children: F, breadth_first: bool) -> TraverseIterator<I, F, C>
+where
+    I: Iterator,
+    F: FnMut(&I::Item) -> C,
+    C: IntoIterator<Item = I::Item>,
+{
+    TraverseIterator {
+        roots: iter.fuse(),
+        children,
+        pending: VecDeque::new(),
+        breadth_first,
+        visitor: VisitAll,
+    }
+}
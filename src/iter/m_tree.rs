@@ -0,0 +1,263 @@
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::Hash;
+
+/// An owned forest built from flat rows that refer to their parent by id.
+///
+/// Nodes keep the order of the source rows among their siblings.
+#[derive(Clone, Debug)]
+pub struct Tree<T, K> {
+    items: Vec<T>,
+    indices: HashMap<K, usize>,
+    parents: Vec<Option<usize>>,
+    children: Vec<Vec<usize>>,
+    depths: Vec<usize>,
+    roots: Vec<usize>,
+}
+
+/// The reason why rows could not be built into a `Tree`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TreeError<K> {
+    /// Several rows have the same id.
+    DuplicateId(K),
+    /// Rows whose parent id does not exist, as `(id, parent_id)` pairs.
+    Orphans(Vec<(K, K)>),
+    /// The ids of rows which are their own ancestors, in parent order.
+    Cycle(Vec<K>),
+}
+
+impl<K: fmt::Debug> fmt::Display for TreeError<K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TreeError::DuplicateId(id) => write!(f, "duplicate id {:?}", id),
+            TreeError::Orphans(orphans) => write!(f, "missing parents for {:?}", orphans),
+            TreeError::Cycle(ids) => write!(f, "cycle between {:?}", ids),
+        }
+    }
+}
+
+impl<K: fmt::Debug> std::error::Error for TreeError<K> {}
+
+impl<T, K> Tree<T, K> {
+    /// Returns the number of nodes.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Returns the nodes without a parent.
+    pub fn roots(&self) -> impl Iterator<Item = Node<'_, T, K>> {
+        self.roots.iter().map(move |index| self.node(*index))
+    }
+
+    /// Returns every node, in the order of the source rows.
+    pub fn nodes(&self) -> impl Iterator<Item = Node<'_, T, K>> {
+        (0..self.items.len()).map(move |index| self.node(index))
+    }
+
+    /// Returns the node built from the `index`-th source row. Use `node_by_id` to find a node by its id.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn node(&self, index: usize) -> Node<'_, T, K> {
+        assert!(index < self.items.len(), "node index out of bounds");
+        Node { tree: self, index }
+    }
+
+    /// Consumes the tree, returning the values in the order of the source rows.
+    pub fn into_values(self) -> Vec<T> {
+        self.items
+    }
+}
+
+impl<T, K: Eq + Hash> Tree<T, K> {
+    /// Returns the node whose row has the given id, or `None` if there is none.
+    pub fn node_by_id<Q>(&self, id: &Q) -> Option<Node<'_, T, K>>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.indices.get(id).map(|index| self.node(*index))
+    }
+}
+
+/// A node borrowed from a `Tree`.
+pub struct Node<'a, T, K> {
+    tree: &'a Tree<T, K>,
+    index: usize,
+}
+
+impl<'a, T, K> Clone for Node<'a, T, K> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, T, K> Copy for Node<'a, T, K> {}
+
+impl<'a, T: fmt::Debug, K> fmt::Debug for Node<'a, T, K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Node").field(self.value()).finish()
+    }
+}
+
+impl<'a, T, K> Node<'a, T, K> {
+    /// Returns the position of the node's row in the source.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    pub fn value(&self) -> &'a T {
+        &self.tree.items[self.index]
+    }
+
+    pub fn parent(&self) -> Option<Node<'a, T, K>> {
+        let tree = self.tree;
+        tree.parents[self.index].map(|index| tree.node(index))
+    }
+
+    pub fn children(&self) -> impl Iterator<Item = Node<'a, T, K>> {
+        let tree = self.tree;
+        tree.children[self.index]
+            .iter()
+            .map(move |index| tree.node(*index))
+    }
+
+    /// Returns the number of ancestors; roots have depth 0.
+    pub fn depth(&self) -> usize {
+        self.tree.depths[self.index]
+    }
+
+    /// Returns this node followed by each of its ancestors, up to the root.
+    pub fn path_to_root(&self) -> impl Iterator<Item = Node<'a, T, K>> {
+        std::iter::successors(Some(*self), |node| node.parent())
+    }
+
+    /// Returns this node followed by all of its descendants, in depth-first pre-order.
+    pub fn subtree(&self) -> impl Iterator<Item = Node<'a, T, K>> {
+        let tree = self.tree;
+        let mut stack = vec![self.index];
+        std::iter::from_fn(move || {
+            let index = stack.pop()?;
+            stack.extend(tree.children[index].iter().rev());
+            Some(tree.node(index))
+        })
+    }
+
+    /// Aggregates the subtree bottom-up: `f` receives each value and the results of its children.
+    pub fn aggregate<B, F>(&self, mut f: F) -> B
+    where
+        F: FnMut(&'a T, Vec<B>) -> B,
+    {
+        let tree = self.tree;
+        // Walk in post-order with an explicit stack so that deep trees cannot overflow the call stack.
+        // Each frame holds a node, how many of its children were entered, and where their results start.
+        let mut results = Vec::new();
+        let mut stack = vec![(self.index, 0, 0)];
+        while let Some((index, entered, start)) = stack.last_mut() {
+            match tree.children[*index].get(*entered) {
+                Some(child) => {
+                    *entered += 1;
+                    let child = (*child, 0, results.len());
+                    stack.push(child);
+                }
+                None => {
+                    let children = results.split_off(*start);
+                    let value = &tree.items[*index];
+                    stack.pop();
+                    results.push(f(value, children));
+                }
+            }
+        }
+        results.pop().unwrap()
+    }
+}
+
+pub fn to_tree<I, K, FI, FP>(
+    iter: I,
+    id: FI,
+    parent_id: FP,
+) -> Result<Tree<I::Item, K>, TreeError<K>>
+where
+    I: Iterator,
+    K: Eq + Hash + Clone,
+    FI: Fn(&I::Item) -> K,
+    FP: Fn(&I::Item) -> Option<K>,
+{
+    let items: Vec<_> = iter.collect();
+    let ids: Vec<K> = items.iter().map(&id).collect();
+
+    let mut indices = HashMap::with_capacity(ids.len());
+    for (index, id) in ids.iter().enumerate() {
+        if indices.insert(id.clone(), index).is_some() {
+            return Err(TreeError::DuplicateId(id.clone()));
+        }
+    }
+
+    let mut parents = Vec::with_capacity(items.len());
+    let mut orphans = Vec::new();
+    for (index, item) in items.iter().enumerate() {
+        let parent = match parent_id(item) {
+            Some(parent) => match indices.get(&parent) {
+                Some(parent) => Some(*parent),
+                None => {
+                    orphans.push((ids[index].clone(), parent));
+                    None
+                }
+            },
+            None => None,
+        };
+        parents.push(parent);
+    }
+    if !orphans.is_empty() {
+        return Err(TreeError::Orphans(orphans));
+    }
+
+    let mut children = vec![Vec::new(); items.len()];
+    let mut roots = Vec::new();
+    for (index, parent) in parents.iter().enumerate() {
+        match parent {
+            Some(parent) => children[*parent].push(index),
+            None => roots.push(index),
+        }
+    }
+
+    let mut depths = vec![usize::MAX; items.len()];
+    let mut stack: Vec<(usize, usize)> = roots.iter().map(|index| (*index, 0)).collect();
+    while let Some((index, depth)) = stack.pop() {
+        depths[index] = depth;
+        stack.extend(children[index].iter().map(|child| (*child, depth + 1)));
+    }
+
+    // Rows which can't be reached from a root are in a cycle, or below one.
+    if let Some(start) = depths.iter().position(|depth| *depth == usize::MAX) {
+        let mut visited = vec![false; items.len()];
+        let mut index = start;
+        while !visited[index] {
+            visited[index] = true;
+            index = parents[index].unwrap();
+        }
+        let cycle_start = index;
+        let mut cycle = vec![ids[index].clone()];
+        index = parents[index].unwrap();
+        while index != cycle_start {
+            cycle.push(ids[index].clone());
+            index = parents[index].unwrap();
+        }
+        return Err(TreeError::Cycle(cycle));
+    }
+
+    Ok(Tree {
+        items,
+        indices,
+        parents,
+        children,
+        depths,
+        roots,
+    })
+}
@@ -45,6 +45,25 @@ where
             }
         }
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let second = self.second_source.size_hint().1;
+        if self.was_first_source_consumed {
+            return (0, second);
+        }
+        match (self.first_source.size_hint().1, second) {
+            (Some(first), Some(second)) => (0, first.checked_add(second)),
+            _ => (0, None),
+        }
+    }
+}
+
+impl<I, U> std::iter::FusedIterator for UnionIterator<I, U>
+where
+    I: Iterator,
+    U: std::iter::FusedIterator<Item = I::Item>,
+    I::Item: Eq + std::hash::Hash + Copy,
+{
 }
 
 pub fn union<I, U>(iter: I, iter_union: U) -> UnionIterator<I, U>
@@ -55,7 +74,7 @@ where
 {
     let hash_map = std::collections::HashSet::new();
     UnionIterator {
-        hash_map: hash_map,
+        hash_map,
         first_source: iter,
         second_source: iter_union,
         was_first_source_consumed: false,
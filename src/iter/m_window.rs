@@ -0,0 +1,185 @@
+use super::m_order_by;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+pub type WindowIterator<T, V> = std::vec::IntoIter<(T, V)>;
+
+#[derive(Clone)]
+pub struct PartitionWindow<I, FP> {
+    source: I,
+    partition: FP,
+}
+
+impl<I, P, FP> PartitionWindow<I, FP>
+where
+    I: Iterator,
+    P: Eq + Hash,
+    FP: Fn(&I::Item) -> P,
+{
+    /// Sorts the elements of each partition in ascending order according to a key.
+    pub fn order_by<K, FK>(self, key: FK) -> OrderedWindow<I::Item, FK>
+    where
+        K: Ord,
+        FK: Fn(&I::Item) -> K,
+    {
+        OrderedWindow {
+            partitions: partition(self.source, self.partition),
+            key,
+            descending: false,
+        }
+    }
+
+    /// Sorts the elements of each partition in descending order according to a key.
+    pub fn order_by_descending<K, FK>(self, key: FK) -> OrderedWindow<I::Item, FK>
+    where
+        K: Ord,
+        FK: Fn(&I::Item) -> K,
+    {
+        OrderedWindow {
+            partitions: partition(self.source, self.partition),
+            key,
+            descending: true,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct OrderedWindow<T, FK> {
+    partitions: Vec<Vec<T>>,
+    key: FK,
+    descending: bool,
+}
+
+impl<T, K, FK> OrderedWindow<T, FK>
+where
+    K: Ord,
+    FK: Fn(&T) -> K,
+{
+    /// Numbers the elements of each partition sequentially, starting at 1.
+    pub fn row_number(self) -> WindowIterator<T, usize> {
+        self.compute(|keys| (1..=keys.len()).collect())
+    }
+
+    /// Ranks the elements of each partition, leaving gaps after ties.
+    pub fn rank(self) -> WindowIterator<T, usize> {
+        self.compute(ranks)
+    }
+
+    /// Ranks the elements of each partition without gaps after ties.
+    pub fn dense_rank(self) -> WindowIterator<T, usize> {
+        self.compute(|keys| {
+            let mut result = Vec::with_capacity(keys.len());
+            for i in 0..keys.len() {
+                let value = match result.last() {
+                    Some(last) if keys[i - 1] == keys[i] => *last,
+                    Some(last) => last + 1,
+                    None => 1,
+                };
+                result.push(value);
+            }
+            result
+        })
+    }
+
+    /// Computes the relative rank `(rank - 1) / (rows - 1)` of the elements of each partition.
+    pub fn percent_rank(self) -> WindowIterator<T, f64> {
+        self.compute(|keys| {
+            let count = keys.len();
+            ranks(keys)
+                .into_iter()
+                .map(|rank| {
+                    if count > 1 {
+                        (rank - 1) as f64 / (count - 1) as f64
+                    } else {
+                        0.0
+                    }
+                })
+                .collect()
+        })
+    }
+
+    /// Distributes the elements of each partition into `n` buckets numbered from 1.
+    ///
+    /// Bucket sizes differ by at most one, with the larger buckets first.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is 0.
+    pub fn ntile(self, n: usize) -> WindowIterator<T, usize> {
+        assert!(n > 0, "ntile requires at least one bucket");
+        self.compute(|keys| {
+            let count = keys.len();
+            let size = count / n;
+            let larger = count % n;
+            (0..count)
+                .map(|i| {
+                    if i < larger * (size + 1) {
+                        i / (size + 1) + 1
+                    } else {
+                        larger + (i - larger * (size + 1)) / size + 1
+                    }
+                })
+                .collect()
+        })
+    }
+
+    fn compute<V, F>(self, mut values: F) -> WindowIterator<T, V>
+    where
+        F: FnMut(&[K]) -> Vec<V>,
+    {
+        let key = &self.key;
+        let mut result = Vec::new();
+        for part in self.partitions {
+            // `order_by` computes the keys once, and they're read back for `values`.
+            let (keys, items): (Vec<K>, Vec<T>) =
+                m_order_by::order_by(part.into_iter(), key, self.descending)
+                    .into_keyed()
+                    .unzip();
+            result.extend(items.into_iter().zip(values(&keys)));
+        }
+        result.into_iter()
+    }
+}
+
+fn ranks<K: Ord>(keys: &[K]) -> Vec<usize> {
+    let mut result: Vec<usize> = Vec::with_capacity(keys.len());
+    for i in 0..keys.len() {
+        let value = if i > 0 && keys[i - 1] == keys[i] {
+            result[i - 1]
+        } else {
+            i + 1
+        };
+        result.push(value);
+    }
+    result
+}
+
+fn partition<I, P, FP>(iter: I, func: FP) -> Vec<Vec<I::Item>>
+where
+    I: Iterator,
+    P: Eq + Hash,
+    FP: Fn(&I::Item) -> P,
+{
+    let mut indices: HashMap<P, usize> = HashMap::new();
+    let mut partitions: Vec<Vec<I::Item>> = Vec::new();
+    for item in iter {
+        let index = *indices.entry(func(&item)).or_insert_with(|| {
+            partitions.push(Vec::new());
+            partitions.len() - 1
+        });
+        partitions[index].push(item);
+    }
+    partitions
+}
+
+pub fn over<I, P, FP>(iter: I, partition: FP) -> PartitionWindow<I, FP>
+where
+    I: Iterator,
+    P: Eq + Hash,
+    FP: Fn(&I::Item) -> P,
+{
+    PartitionWindow {
+        source: iter,
+        partition,
+    }
+}
@@ -0,0 +1,162 @@
+/// A value which may come from the first sequence, the second sequence, or both.
+///
+/// Yielded by `Enumerable::zip_longest`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EitherOrBoth<A, B> {
+    Both(A, B),
+    Left(A),
+    Right(B),
+}
+
+impl<A, B> EitherOrBoth<A, B> {
+    /// Returns the value from the first sequence, if present.
+    pub fn left(self) -> Option<A> {
+        match self {
+            EitherOrBoth::Both(a, _) | EitherOrBoth::Left(a) => Some(a),
+            EitherOrBoth::Right(_) => None,
+        }
+    }
+
+    /// Returns the value from the second sequence, if present.
+    pub fn right(self) -> Option<B> {
+        match self {
+            EitherOrBoth::Both(_, b) | EitherOrBoth::Right(b) => Some(b),
+            EitherOrBoth::Left(_) => None,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct ZipWithIterator<I, U, F> {
+    first: I,
+    second: U,
+    func: F,
+}
+
+impl<I, U, F, R> Iterator for ZipWithIterator<I, U, F>
+where
+    I: Iterator,
+    U: Iterator,
+    F: FnMut(I::Item, U::Item) -> R,
+{
+    type Item = R;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let a = self.first.next()?;
+        let b = self.second.next()?;
+        Some((self.func)(a, b))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        min_size_hint(self.first.size_hint(), self.second.size_hint())
+    }
+}
+
+pub fn zip_with<I, U, F, R>(iter: I, other: U, func: F) -> ZipWithIterator<I, U::IntoIter, F>
+where
+    I: Iterator,
+    U: IntoIterator,
+    F: FnMut(I::Item, U::Item) -> R,
+{
+    ZipWithIterator {
+        first: iter,
+        second: other.into_iter(),
+        func,
+    }
+}
+
+#[derive(Clone)]
+pub struct Zip3Iterator<I, U, V> {
+    first: I,
+    second: U,
+    third: V,
+}
+
+impl<I, U, V> Iterator for Zip3Iterator<I, U, V>
+where
+    I: Iterator,
+    U: Iterator,
+    V: Iterator,
+{
+    type Item = (I::Item, U::Item, V::Item);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let a = self.first.next()?;
+        let b = self.second.next()?;
+        let c = self.third.next()?;
+        Some((a, b, c))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        min_size_hint(
+            min_size_hint(self.first.size_hint(), self.second.size_hint()),
+            self.third.size_hint(),
+        )
+    }
+}
+
+pub fn zip3<I, U, V>(iter: I, second: U, third: V) -> Zip3Iterator<I, U::IntoIter, V::IntoIter>
+where
+    I: Iterator,
+    U: IntoIterator,
+    V: IntoIterator,
+{
+    Zip3Iterator {
+        first: iter,
+        second: second.into_iter(),
+        third: third.into_iter(),
+    }
+}
+
+#[derive(Clone)]
+pub struct ZipLongestIterator<I, U> {
+    first: std::iter::Fuse<I>,
+    second: std::iter::Fuse<U>,
+}
+
+impl<I, U> Iterator for ZipLongestIterator<I, U>
+where
+    I: Iterator,
+    U: Iterator,
+{
+    type Item = EitherOrBoth<I::Item, U::Item>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match (self.first.next(), self.second.next()) {
+            (Some(a), Some(b)) => Some(EitherOrBoth::Both(a, b)),
+            (Some(a), None) => Some(EitherOrBoth::Left(a)),
+            (None, Some(b)) => Some(EitherOrBoth::Right(b)),
+            (None, None) => None,
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (first_lower, first_upper) = self.first.size_hint();
+        let (second_lower, second_upper) = self.second.size_hint();
+        let upper = match (first_upper, second_upper) {
+            (Some(x), Some(y)) => Some(std::cmp::max(x, y)),
+            _ => None,
+        };
+        (std::cmp::max(first_lower, second_lower), upper)
+    }
+}
+
+pub fn zip_longest<I, U>(iter: I, other: U) -> ZipLongestIterator<I, U::IntoIter>
+where
+    I: Iterator,
+    U: IntoIterator,
+{
+    ZipLongestIterator {
+        first: iter.fuse(),
+        second: other.into_iter().fuse(),
+    }
+}
+
+fn min_size_hint(a: (usize, Option<usize>), b: (usize, Option<usize>)) -> (usize, Option<usize>) {
+    let upper = match (a.1, b.1) {
+        (Some(x), Some(y)) => Some(std::cmp::min(x, y)),
+        (Some(x), None) | (None, Some(x)) => Some(x),
+        (None, None) => None,
+    };
+    (std::cmp::min(a.0, b.0), upper)
+}
@@ -2,11 +2,49 @@
 
 mod average;
 mod m_builtin;
+mod m_combinatorics;
 mod m_distinct;
 mod m_enumerable;
+mod m_external;
+mod m_float;
+mod m_histogram;
+mod m_join;
+mod m_merge;
 mod m_method;
 mod m_order_by;
+mod m_radix;
+mod m_random;
+mod m_scan;
 mod m_select;
+mod m_sketch;
+mod m_sliding;
+mod m_sorted;
+mod m_statistics;
+mod m_topological;
+mod m_traverse;
+mod m_tree;
 mod m_union;
+mod m_window;
+mod m_zip;
+mod random;
+mod spill;
 
+pub use m_builtin::{AppendIterator, ConcateAllIterator, DefaultIfEmptyIterator, PrependIterator};
 pub use m_enumerable::*;
+pub use m_float::{Float, NanPlacement, TotalFloat};
+pub use m_histogram::{Bin, Binning, Bucket};
+pub use m_merge::merge_sorted_all;
+pub use m_method::SequenceDifference;
+pub use m_radix::RadixKey;
+pub use m_sketch::{HyperLogLog, QuantileSketch};
+pub use m_sliding::{
+    MovingAverageIterator, MovingExtremumIterator, MovingSumIterator, SlidingWindow,
+    WindowSelectIterator,
+};
+pub use m_sorted::{SortedOperation, UnsortedError, ValidatedIterator};
+pub use m_statistics::{Interpolation, LinearRegression, VarianceKind};
+pub use m_topological::TopologicalSortError;
+pub use m_tree::{Node, Tree, TreeError};
+pub use m_window::{OrderedWindow, PartitionWindow};
+pub use m_zip::EitherOrBoth;
+pub use spill::Spill;
@@ -0,0 +1,177 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Encodes and decodes values which are spilled to temporary files.
+///
+/// Each value is written as one record, so `decode` gets exactly the bytes which `encode` appended.
+pub trait Spill: Sized {
+    fn encode(&self, buffer: &mut Vec<u8>);
+
+    fn decode(bytes: &[u8]) -> io::Result<Self>;
+}
+
+fn invalid_length(expected: usize, found: usize) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("expected {} bytes, found {}", expected, found),
+    )
+}
+
+macro_rules! spill_bytes {
+    ($($t:ty),*) => {
+        $(
+            impl Spill for $t {
+                fn encode(&self, buffer: &mut Vec<u8>) {
+                    buffer.extend_from_slice(&self.to_le_bytes());
+                }
+
+                fn decode(bytes: &[u8]) -> io::Result<Self> {
+                    let mut array = [0; std::mem::size_of::<$t>()];
+                    if bytes.len() != array.len() {
+                        return Err(invalid_length(array.len(), bytes.len()));
+                    }
+                    array.copy_from_slice(bytes);
+                    Ok(<$t>::from_le_bytes(array))
+                }
+            }
+        )*
+    };
+}
+
+spill_bytes!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64);
+
+impl Spill for String {
+    fn encode(&self, buffer: &mut Vec<u8>) {
+        buffer.extend_from_slice(self.as_bytes());
+    }
+
+    fn decode(bytes: &[u8]) -> io::Result<Self> {
+        String::from_utf8(bytes.to_vec()).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+}
+
+impl Spill for Vec<u8> {
+    fn encode(&self, buffer: &mut Vec<u8>) {
+        buffer.extend_from_slice(self);
+    }
+
+    fn decode(bytes: &[u8]) -> io::Result<Self> {
+        Ok(bytes.to_vec())
+    }
+}
+
+static NEXT_FILE: AtomicUsize = AtomicUsize::new(0);
+
+/// A temporary file of length-prefixed records, which is removed when dropped.
+pub struct SpillFile {
+    path: PathBuf,
+}
+
+impl SpillFile {
+    pub fn create(dir: &Path) -> io::Result<(SpillFile, SpillWriter)> {
+        loop {
+            let path = dir.join(format!(
+                "linq-spill-{}-{}",
+                std::process::id(),
+                NEXT_FILE.fetch_add(1, Ordering::Relaxed)
+            ));
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(file) => {
+                    let writer = SpillWriter {
+                        writer: BufWriter::new(file),
+                        buffer: Vec::new(),
+                        records: 0,
+                        bytes: 0,
+                    };
+                    return Ok((SpillFile { path }, writer));
+                }
+                // A file left by another process with the same id.
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    pub fn open(&self) -> io::Result<SpillReader> {
+        Ok(SpillReader {
+            reader: BufReader::new(File::open(&self.path)?),
+            buffer: Vec::new(),
+        })
+    }
+}
+
+impl Drop for SpillFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+pub struct SpillWriter {
+    writer: BufWriter<File>,
+    buffer: Vec<u8>,
+    records: usize,
+    bytes: usize,
+}
+
+impl SpillWriter {
+    pub fn write<T: Spill>(&mut self, item: &T) -> io::Result<()> {
+        let mut buffer = std::mem::take(&mut self.buffer);
+        buffer.clear();
+        item.encode(&mut buffer);
+        let written = self.write_encoded(&buffer);
+        self.buffer = buffer;
+        written
+    }
+
+    /// Writes a record which was already encoded by `Spill::encode`.
+    pub fn write_encoded(&mut self, bytes: &[u8]) -> io::Result<()> {
+        self.writer.write_all(&(bytes.len() as u64).to_le_bytes())?;
+        self.writer.write_all(bytes)?;
+        self.records += 1;
+        self.bytes += bytes.len();
+        Ok(())
+    }
+
+    /// Returns the count of records written.
+    pub fn records(&self) -> usize {
+        self.records
+    }
+
+    /// Returns the count of encoded bytes written, without the length prefixes.
+    pub fn bytes(&self) -> usize {
+        self.bytes
+    }
+
+    pub fn finish(mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+pub struct SpillReader {
+    reader: BufReader<File>,
+    buffer: Vec<u8>,
+}
+
+impl SpillReader {
+    /// Reads the next record, or `None` at the end of the file.
+    pub fn read<T: Spill>(&mut self) -> io::Result<Option<T>> {
+        match self.read_encoded()? {
+            Some(bytes) => T::decode(bytes).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Reads the bytes of the next record without decoding them.
+    pub fn read_encoded(&mut self) -> io::Result<Option<&[u8]>> {
+        if self.reader.fill_buf()?.is_empty() {
+            return Ok(None);
+        }
+        let mut length = [0; 8];
+        self.reader.read_exact(&mut length)?;
+        self.buffer.resize(u64::from_le_bytes(length) as usize, 0);
+        self.reader.read_exact(&mut self.buffer)?;
+        Ok(Some(&self.buffer))
+    }
+}
@@ -93,6 +93,14 @@ macro_rules! linq {
     {
         $c0.select_many(|$v0| $c, |$v0, $v| $ms).distinct()
     };
+    (from $v0:ident in $c0:expr, zip $v:ident in $c:expr, select $ms:expr) =>
+    {
+        $c0.zip($c).select(|($v0, $v)| $ms)
+    };
+    (from $v0:ident in $c0:expr, zip $v:ident in $c:expr, select distinct $ms:expr) =>
+    {
+        $c0.zip($c).select(|($v0, $v)| $ms).distinct()
+    };
 }
 
 #[cfg(test)]
@@ -44,6 +44,14 @@ fn select_many_zip() {
     assert_eq!(e, y);
 }
 
+#[test]
+fn zip() {
+    let x = 1..5;
+    let y = vec![(1, 'a'), (2, 'b'), (3, 'c')];
+    let e: Vec<_> = linq!(from p in x.clone(), zip t in "abc".chars(), select (p, t)).collect();
+    assert_eq!(e, y);
+}
+
 #[test]
 fn where_by() {
     let x = 1..100;
@@ -18,6 +18,203 @@ fn average() {
     assert_eq!(float_avg, 12.5);
 }
 
+#[test]
+fn median_percentile() {
+    use crate::iter::Interpolation;
+
+    assert_eq!(vec![3, 1, 2].into_iter().median(|p| *p as f64), Some(2.0));
+    assert_eq!((1..5).median(|p| *p as f64), Some(2.5));
+    assert_eq!((0..0).median(|p| *p as f64), None);
+
+    let x = [10.0, 40.0, 20.0, 30.0, 50.0];
+    let percentile = |p, i| x.iter().percentile(p, i, |v| **v);
+    assert_eq!(percentile(0.0, Interpolation::Linear), Some(10.0));
+    assert_eq!(percentile(100.0, Interpolation::Linear), Some(50.0));
+    assert_eq!(percentile(40.0, Interpolation::Linear), Some(26.0));
+    assert_eq!(percentile(40.0, Interpolation::Lower), Some(20.0));
+    assert_eq!(percentile(40.0, Interpolation::Higher), Some(30.0));
+    assert_eq!(percentile(40.0, Interpolation::Nearest), Some(30.0));
+    assert_eq!(percentile(40.0, Interpolation::Midpoint), Some(25.0));
+
+    let x = [10.0, f64::NAN, 40.0, 20.0, 30.0, -f64::NAN, 50.0];
+    assert_eq!(x.iter().median(|v| **v), Some(30.0));
+    assert_eq!(
+        x.iter().percentile(100.0, Interpolation::Linear, |v| **v),
+        Some(50.0)
+    );
+    assert_eq!(std::iter::once(f64::NAN).median(|v| *v), None);
+}
+
+#[test]
+fn mode() {
+    let x = ["a", "bb", "cc", "d", "ee"];
+    assert_eq!(x.iter().mode(|p| p.len()), Some(2));
+    assert_eq!(x.iter().take(4).mode(|p| p.len()), Some(1));
+    assert_eq!((0..0).mode(|p| *p), None);
+}
+
+#[test]
+fn variance() {
+    use crate::iter::VarianceKind;
+
+    let x = [2, 4, 4, 4, 5, 5, 7, 9];
+    assert_eq!(
+        x.iter().variance(VarianceKind::Population, |p| **p as f64),
+        Some(4.0)
+    );
+    assert_eq!(
+        x.iter().std_dev(VarianceKind::Population, |p| **p as f64),
+        Some(2.0)
+    );
+    assert_eq!(
+        x.iter().variance(VarianceKind::Sample, |p| **p as f64),
+        Some(32.0 / 7.0)
+    );
+    assert_eq!(
+        (0..1).variance(VarianceKind::Population, |p| *p as f64),
+        Some(0.0)
+    );
+    assert_eq!((0..1).variance(VarianceKind::Sample, |p| *p as f64), None);
+    assert_eq!(
+        (0..0).std_dev(VarianceKind::Population, |p| *p as f64),
+        None
+    );
+}
+
+#[test]
+fn min_max() {
+    let x = ["bb", "a", "ccc", "d", "eee"];
+    assert_eq!(x.iter().min_max(|p| p.len()), Some((&"a", &"eee")));
+    assert_eq!((0..0).min_max(|p| *p), None);
+    assert_eq!((0..1).min_max(|p| *p), Some((0, 0)));
+    assert_eq!([3, 1, 3].iter().min_max(|p| **p), Some((&1, &3)));
+
+    let calls = std::cell::Cell::new(0);
+    let e = (0..10).min_max(|p| {
+        calls.set(calls.get() + 1);
+        -p
+    });
+    assert_eq!(e, Some((9, 0)));
+    assert_eq!(calls.get(), 10);
+}
+
+#[test]
+fn covariance_correlation() {
+    use crate::iter::VarianceKind;
+
+    let x = [(1.0, 2.0), (2.0, 4.5), (3.0, 5.5), (4.0, 8.0)];
+    let population = x
+        .iter()
+        .covariance(VarianceKind::Population, |p| p.0, |p| p.1);
+    let sample = x.iter().covariance(VarianceKind::Sample, |p| p.0, |p| p.1);
+    assert!((population.unwrap() - 2.375).abs() < 1e-12);
+    assert!((sample.unwrap() - 3.1666666666666665).abs() < 1e-12);
+
+    let r = x.iter().correlation(|p| p.0, |p| p.1).unwrap();
+    assert!((r - 0.9877629653290692).abs() < 1e-12);
+    assert_eq!((0..5).correlation(|p| *p as f64, |_| 1.0), None);
+    assert_eq!((0..1).correlation(|p| *p as f64, |p| *p as f64), None);
+}
+
+#[test]
+fn spearman_correlation() {
+    let x = [(1, 1), (2, 8), (3, 27), (4, 64)];
+    assert_eq!(x.iter().spearman_correlation(|p| p.0, |p| p.1), Some(1.0));
+    assert_eq!(x.iter().spearman_correlation(|p| p.0, |p| -p.1), Some(-1.0));
+
+    let x = [(1, 2), (2, 1), (3, 1), (4, 3)];
+    let r = x.iter().spearman_correlation(|p| p.0, |p| p.1).unwrap();
+    assert!((r - 0.3162277660168379).abs() < 1e-12);
+}
+
+#[test]
+fn linear_regression() {
+    let x = [(0.0, 1.0), (1.0, 3.5), (2.0, 4.5), (3.0, 7.0)];
+    let e = x.iter().linear_regression(|p| p.0, |p| p.1).unwrap();
+    assert!((e.slope - 1.9).abs() < 1e-12);
+    assert!((e.intercept - 1.15).abs() < 1e-12);
+    assert!((e.r_squared - 0.9756756756756759).abs() < 1e-12);
+
+    assert!((0..5).linear_regression(|_| 1.0, |p| *p as f64).is_none());
+}
+
+#[test]
+fn histogram() {
+    use crate::iter::{Bin, Binning};
+
+    let bin = |start, end, count| Bin { start, end, count };
+
+    assert_eq!(
+        (0..10).histogram(Binning::EqualWidth(3), |p| *p as f64),
+        vec![bin(0.0, 3.0, 3), bin(3.0, 6.0, 3), bin(6.0, 9.0, 4)]
+    );
+    assert_eq!(
+        (0..10).histogram(Binning::Edges(vec![2.0, 4.0, 5.0]), |p| *p as f64),
+        vec![bin(2.0, 4.0, 2), bin(4.0, 5.0, 2)]
+    );
+    assert_eq!(
+        (1..10).histogram(Binning::Quantile(2), |p| *p as f64),
+        vec![bin(1.0, 5.0, 4), bin(5.0, 9.0, 5)]
+    );
+    assert!((0..0)
+        .histogram(Binning::EqualWidth(3), |p| *p as f64)
+        .is_empty());
+    assert_eq!(
+        vec![1.0, 1.0, f64::NAN]
+            .into_iter()
+            .histogram(Binning::EqualWidth(2), |p| *p),
+        vec![bin(1.0, 1.0, 2)]
+    );
+    assert_eq!(
+        vec![1.0, 1.0, 1.0]
+            .into_iter()
+            .histogram(Binning::Quantile(3), |p| *p),
+        vec![bin(1.0, 1.0, 3)]
+    );
+
+    // The range overflows, but the edges don't.
+    let x = [-1e308, 1e308];
+    assert_eq!(
+        x.iter().histogram(Binning::EqualWidth(2), |p| **p),
+        vec![bin(-1e308, 0.0, 1), bin(0.0, 1e308, 1)]
+    );
+    assert_eq!(
+        x.iter().histogram(Binning::Quantile(2), |p| **p),
+        vec![bin(-1e308, 0.0, 1), bin(0.0, 1e308, 1)]
+    );
+
+    let x = [f64::NEG_INFINITY, 0.0, 1.0, 2.0, f64::INFINITY];
+    assert_eq!(
+        x.iter().histogram(Binning::EqualWidth(2), |p| **p),
+        vec![bin(0.0, 1.0, 1), bin(1.0, 2.0, 2)]
+    );
+    assert_eq!(
+        x.iter().histogram(Binning::Quantile(2), |p| **p),
+        vec![bin(0.0, 1.0, 1), bin(1.0, 2.0, 2)]
+    );
+    assert_eq!(
+        x.iter().histogram(
+            Binning::Edges(vec![f64::NEG_INFINITY, 0.0, f64::INFINITY]),
+            |p| **p
+        ),
+        vec![bin(f64::NEG_INFINITY, 0.0, 1), bin(0.0, f64::INFINITY, 4)]
+    );
+    assert!(vec![f64::INFINITY]
+        .into_iter()
+        .histogram(Binning::EqualWidth(2), |p| *p)
+        .is_empty());
+}
+
+#[test]
+fn bucket_by() {
+    let e = (0..10).bucket_by(&[1.0, 5.0, 8.0], |p| *p as f64);
+    assert_eq!(e.len(), 2);
+    assert_eq!((e[0].start, e[0].end), (1.0, 5.0));
+    assert_eq!(e[0].items, vec![1, 2, 3, 4]);
+    assert_eq!((e[1].start, e[1].end), (5.0, 8.0));
+    assert_eq!(e[1].items, vec![5, 6, 7, 8]);
+}
+
 #[test]
 fn select_many() {
     let x = 1..5;
@@ -26,6 +223,29 @@ fn select_many() {
     assert_eq!(e, y);
 }
 
+#[test]
+fn select_many_double_ended() {
+    let e: Vec<i32> = (1..5).select_many_single(|p| 0..p).reverse().collect();
+    assert_eq!(e, vec![3, 2, 1, 0, 2, 1, 0, 1, 0, 0]);
+
+    let mut iter = (1..4).select_many_single(|p| 0..p);
+    assert_eq!(iter.size_hint(), (0, None));
+    assert_eq!(iter.next(), Some(0));
+    assert_eq!(iter.next_back(), Some(2));
+    assert_eq!(iter.next(), Some(0));
+    assert_eq!(iter.next_back(), Some(1));
+    assert_eq!(iter.size_hint(), (2, Some(2)));
+    assert_eq!(iter.next_back(), Some(0));
+    assert_eq!(iter.next(), Some(1));
+    assert_eq!(iter.next(), None);
+    assert_eq!(iter.next_back(), None);
+
+    let iter = (1..3).select_many(|p| 0..p, |p, t| p * 10 + t);
+    assert_eq!(iter.size_hint(), (0, None));
+    let iter = std::iter::empty::<i32>().select_many(|p| 0..p, |p, t| p * 10 + t);
+    assert_eq!(iter.size_hint(), (0, Some(0)));
+}
+
 #[test]
 fn select_many_zip() {
     let x = 1..5;
@@ -77,6 +297,441 @@ fn order_by() {
     assert_eq!(e, y);
 }
 
+#[test]
+fn order_by_stable() {
+    let x = [(2, 'a'), (1, 'b'), (2, 'c'), (1, 'd'), (3, 'e')];
+    let e: Vec<char> = x.iter().order_by(|p| p.0).select(|p| p.1).collect();
+    assert_eq!(e, vec!['b', 'd', 'a', 'c', 'e']);
+    let e: Vec<char> = x
+        .iter()
+        .order_by_descending(|p| p.0)
+        .select(|p| p.1)
+        .collect();
+    // Descending order is the exact reverse of ascending order, ties included.
+    assert_eq!(e, vec!['e', 'c', 'a', 'd', 'b']);
+    let e: Vec<char> = x
+        .iter()
+        .order_by_descending_uncached(|p| p.0)
+        .select(|p| p.1)
+        .collect();
+    assert_eq!(e, vec!['e', 'c', 'a', 'd', 'b']);
+    let e: Vec<char> = x.iter().top_k(3, |p| p.0).select(|p| p.1).collect();
+    assert_eq!(e, vec!['e', 'c', 'a']);
+}
+
+#[test]
+fn order_by_lazy() {
+    let mut iter = (0..1000).select(|p| (p * 7919) % 1000).order_by(|p| *p);
+    assert_eq!(iter.size_hint(), (1000, Some(1000)));
+    assert_eq!(iter.next(), Some(0));
+    assert_eq!(iter.next(), Some(1));
+    assert_eq!(iter.size_hint(), (998, Some(998)));
+    assert!(iter.eq(2..1000));
+
+    // Switching from the heap to a sort part way through, or folding, keeps the same order.
+    let x: Vec<(i32, i32)> = (0..1000).select(|p| ((p * 7919) % 100, p)).collect();
+    let mut y = x.clone();
+    y.sort_by_key(|p| p.0);
+    for descending in [false, true] {
+        if descending {
+            y.reverse();
+        }
+        let key = |p: &&(i32, i32)| p.0;
+        let mut iter = if descending {
+            x.iter().order_by_descending(key)
+        } else {
+            x.iter().order_by(key)
+        };
+        let mut e: Vec<_> = iter.by_ref().take(10).copied().collect();
+        e.extend(iter.by_ref().take(300).copied());
+        iter.for_each(|p| e.push(*p));
+        assert_eq!(e, y);
+    }
+
+    // `count` may exceed the length of the source.
+    let e: Vec<i32> = (0..5).top_k(usize::MAX, |p| *p).collect();
+    assert_eq!(e, vec![4, 3, 2, 1, 0]);
+}
+
+#[test]
+fn order_by_double_ended() {
+    let x = [(2, 'a'), (1, 'b'), (2, 'c'), (1, 'd'), (3, 'e')];
+    let e: Vec<char> = x
+        .iter()
+        .order_by(|p| p.0)
+        .reverse()
+        .select(|p| p.1)
+        .collect();
+    assert_eq!(e, vec!['e', 'c', 'a', 'd', 'b']);
+
+    let mut iter = x.iter().order_by_descending(|p| p.0).select(|p| p.1);
+    assert_eq!(iter.len(), 5);
+    assert_eq!(iter.next(), Some('e'));
+    assert_eq!(iter.next_back(), Some('b'));
+    assert_eq!(iter.len(), 3);
+    assert_eq!(iter.next(), Some('c'));
+    assert_eq!(iter.next_back(), Some('d'));
+    assert_eq!(iter.next(), Some('a'));
+    assert_eq!(iter.len(), 0);
+    assert_eq!(iter.next_back(), None);
+    assert_eq!(iter.next(), None);
+
+    let e: Vec<i32> = (0..10).bottom_k(4, |p| *p).rev().collect();
+    assert_eq!(e, vec![3, 2, 1, 0]);
+}
+
+#[test]
+fn order_by_key_calls() {
+    use std::cell::Cell;
+
+    let x: Vec<i32> = (0..1000).select(|p| (p * 7919) % 1000).collect();
+
+    let calls = Cell::new(0);
+    let mut y = x.clone();
+    y.sort_by_key(|p| {
+        calls.set(calls.get() + 1);
+        p.to_string()
+    });
+    let sort_calls = calls.get();
+
+    calls.set(0);
+    let e: Vec<i32> = x
+        .iter()
+        .order_by(|p| {
+            calls.set(calls.get() + 1);
+            p.to_string()
+        })
+        .copied()
+        .collect();
+    assert_eq!(e, y);
+    assert_eq!(calls.get(), x.len());
+    assert!(sort_calls > x.len());
+
+    calls.set(0);
+    let e: Vec<i32> = x
+        .iter()
+        .order_by_descending(|p| {
+            calls.set(calls.get() + 1);
+            p.to_string()
+        })
+        .copied()
+        .collect();
+    assert_eq!(e, y.iter().rev().copied().collect::<Vec<_>>());
+    assert_eq!(calls.get(), x.len());
+
+    calls.set(0);
+    let e: Vec<i32> = x
+        .iter()
+        .order_by_uncached(|p| {
+            calls.set(calls.get() + 1);
+            p.to_string()
+        })
+        .copied()
+        .collect();
+    assert_eq!(e, y);
+    assert!(calls.get() > x.len());
+}
+
+#[test]
+fn order_by_ref() {
+    struct Person {
+        name: String,
+        age: u32,
+    }
+
+    let x = vec![
+        Person {
+            name: String::from("carol"),
+            age: 35,
+        },
+        Person {
+            name: String::from("alice"),
+            age: 30,
+        },
+        Person {
+            name: String::from("bob"),
+            age: 30,
+        },
+        Person {
+            name: String::from("alice"),
+            age: 25,
+        },
+    ];
+    let e: Vec<u32> = x
+        .iter()
+        .order_by_ref(|p| &p.name)
+        .select(|p| p.age)
+        .collect();
+    assert_eq!(e, vec![30, 25, 30, 35]);
+    let e: Vec<u32> = x
+        .iter()
+        .order_by_ref_descending(|p| p.name.as_str())
+        .select(|p| p.age)
+        .collect();
+    assert_eq!(e, vec![35, 30, 25, 30]);
+
+    let e: Vec<String> = x
+        .into_iter()
+        .order_by_ref(|p| &p.age)
+        .select(|p| p.name)
+        .collect();
+    assert_eq!(e, vec!["alice", "alice", "bob", "carol"]);
+}
+
+#[test]
+fn order_by_radix() {
+    let x: Vec<(i64, u32)> = (0..1000i64)
+        .select(|p| ((p * 7919) % 1000 - 500) * 1_000_003)
+        .zip(0..)
+        .collect();
+
+    let e: Vec<_> = x.iter().order_by_radix(|p| p.0).collect();
+    let y: Vec<_> = x.iter().order_by(|p| p.0).collect();
+    assert_eq!(e, y);
+    let e: Vec<_> = x.iter().order_by_radix_descending(|p| p.0).collect();
+    let y: Vec<_> = x.iter().order_by_descending(|p| p.0).collect();
+    assert_eq!(e, y);
+
+    let e: Vec<_> = x
+        .iter()
+        .order_by_radix(|p| (p.0 % 7, p.1 % 3 == 0))
+        .collect();
+    let y: Vec<_> = x.iter().order_by(|p| (p.0 % 7, p.1 % 3 == 0)).collect();
+    assert_eq!(e, y);
+    let e: Vec<_> = x
+        .iter()
+        .order_by_radix_descending(|p| (p.1 as u8, i16::MIN, p.0 as i8))
+        .collect();
+    let y: Vec<_> = x
+        .iter()
+        .order_by_descending(|p| (p.1 as u8, i16::MIN, p.0 as i8))
+        .collect();
+    assert_eq!(e, y);
+
+    let e: Vec<u128> = vec![u128::MAX, 0, 1 << 100, 7]
+        .into_iter()
+        .order_by_radix(|p| *p)
+        .collect();
+    assert_eq!(e, vec![0, 7, 1 << 100, u128::MAX]);
+    assert_eq!(std::iter::empty::<u8>().order_by_radix(|p| *p).count(), 0);
+}
+
+#[test]
+fn order_by_float() {
+    use crate::iter::{NanPlacement, TotalFloat};
+
+    let x = [1.5, -f64::NAN, 0.0, f64::INFINITY, -0.0, f64::NAN, -2.0];
+    let e: Vec<u64> = x
+        .iter()
+        .order_by_float(|p| **p)
+        .map(|p| p.to_bits())
+        .collect();
+    let y: Vec<u64> = [-f64::NAN, -2.0, -0.0, 0.0, 1.5, f64::INFINITY, f64::NAN]
+        .iter()
+        .map(|p| p.to_bits())
+        .collect();
+    assert_eq!(e, y);
+    let e: Vec<u64> = x
+        .iter()
+        .order_by_float_descending(|p| **p)
+        .map(|p| p.to_bits())
+        .collect();
+    assert_eq!(e, y.into_iter().rev().collect::<Vec<_>>());
+
+    let e: Vec<usize> = (0..x.len())
+        .order_by(|p| TotalFloat::with_nan(x[*p], NanPlacement::First))
+        .collect();
+    assert_eq!(e, vec![1, 5, 6, 4, 2, 0, 3]);
+    let e: Vec<usize> = (0..x.len())
+        .order_by(|p| TotalFloat::with_nan(x[*p], NanPlacement::Last))
+        .collect();
+    assert_eq!(e, vec![6, 4, 2, 0, 3, 1, 5]);
+    assert_eq!(TotalFloat(0.5f32), TotalFloat(0.5));
+    assert_ne!(TotalFloat(0.0), TotalFloat(-0.0));
+}
+
+#[test]
+fn distinct_float() {
+    let x = vec![0.5, f64::NAN, -0.0, 0.5, 0.0, f64::NAN, -0.0];
+    let e: Vec<u64> = x
+        .into_iter()
+        .distinct_float()
+        .map(|p| p.to_bits())
+        .collect();
+    let y: Vec<u64> = [0.5, f64::NAN, -0.0, 0.0]
+        .iter()
+        .map(|p| p.to_bits())
+        .collect();
+    assert_eq!(e, y);
+
+    let iter = [1.0, 2.0].iter().copied().distinct_float();
+    assert_eq!(iter.size_hint(), (0, Some(2)));
+}
+
+#[test]
+fn min_max_float() {
+    let x = [f64::NAN, 0.0, 3.5, -0.0, -f64::NAN, -1.5, 3.5];
+    assert_eq!(x.iter().copied().min_float(), Some(-1.5));
+    assert_eq!(x.iter().copied().max_float(), Some(3.5));
+    let x = [0.0f32, -0.0];
+    assert!(x.iter().copied().min_float().unwrap().is_sign_negative());
+    assert!(x.iter().copied().max_float().unwrap().is_sign_positive());
+    assert_eq!(std::iter::once(f32::NAN).max_float(), None);
+    assert_eq!(std::iter::empty::<f64>().min_float(), None);
+}
+
+#[test]
+fn order_by_external() {
+    let dir = std::env::temp_dir().join(format!("linq-order-by-external-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+
+    let x: Vec<u64> = (0..10_000u64).select(|p| (p * 7919) % 10_000).collect();
+    // With its key and buffer, each element takes 40 bytes, which makes 400 runs merged in two passes.
+    let mut iter = x
+        .iter()
+        .copied()
+        .order_by_external(|p| p % 100, 1000, &dir)
+        .unwrap();
+    assert!(std::fs::read_dir(&dir).unwrap().count() > 0);
+    let e: Vec<u64> = iter.by_ref().take(10).map(|p| p.unwrap()).collect();
+    let y: Vec<u64> = x.iter().copied().order_by(|p| p % 100).collect();
+    assert_eq!(e, y[..10]);
+    assert_eq!(iter.map(|p| p.unwrap()).collect::<Vec<_>>(), y[10..]);
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 0);
+
+    let e: Vec<u64> = x
+        .iter()
+        .copied()
+        .order_by_external_descending(|p| p % 100, 1000, &dir)
+        .unwrap()
+        .map(|p| p.unwrap())
+        .collect();
+    let y: Vec<u64> = x.iter().copied().order_by_descending(|p| p % 100).collect();
+    assert_eq!(e, y);
+
+    // Everything fits in memory, so nothing is spilled.
+    let iter = x
+        .iter()
+        .copied()
+        .order_by_external(|p| *p, usize::MAX, &dir)
+        .unwrap();
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 0);
+    assert!(iter.map(|p| p.unwrap()).eq(0..10_000));
+
+    // A zero budget still puts several elements in each run.
+    let iter = x[..100]
+        .iter()
+        .copied()
+        .order_by_external(|p| *p, 0, &dir)
+        .unwrap();
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 100 / 16);
+    let mut y = x[..100].to_vec();
+    y.sort_unstable();
+    assert_eq!(iter.map(|p| p.unwrap()).collect::<Vec<_>>(), y);
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 0);
+
+    std::fs::remove_dir(&dir).unwrap();
+}
+
+#[test]
+fn grace_join() {
+    let dir = std::env::temp_dir().join(format!("linq-grace-join-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+
+    let x: Vec<u32> = (0..2000).collect();
+    let y: Vec<u64> = (0..3000u64).select(|p| (p * 7919) % 3000).collect();
+    let mut expected: Vec<(u32, u64)> = x
+        .iter()
+        .select_many(
+            |a| y.iter().filter(move |b| **b % 1000 == *a as u64),
+            |a, b| (*a, *b),
+        )
+        .collect();
+    expected.sort_unstable();
+
+    let join = |budget: usize| -> Vec<(u32, u64)> {
+        let mut e: Vec<(u32, u64)> = x
+            .iter()
+            .copied()
+            .grace_join(
+                y.iter().copied(),
+                |a| *a as u64,
+                |b| b % 1000,
+                |a, b| (*a, *b),
+                budget,
+                &dir,
+            )
+            .unwrap()
+            .map(|p| p.unwrap())
+            .collect();
+        e.sort_unstable();
+        e
+    };
+    // In memory, partitioned once, and partitioned again.
+    assert_eq!(join(1 << 20), expected);
+    assert_eq!(join(8000), expected);
+    assert_eq!(join(100), expected);
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 0);
+
+    let e: Vec<(u32, u64)> = x
+        .iter()
+        .copied()
+        .grace_join(
+            y.iter().copied(),
+            |a| *a as u64,
+            |b| *b,
+            |a, b| (*a, *b),
+            1 << 20,
+            &dir,
+        )
+        .unwrap()
+        .map(|p| p.unwrap())
+        .collect();
+    assert_eq!(e, (0..2000).map(|p| (p, p as u64)).collect::<Vec<_>>());
+
+    // One hot key can't be split, so its partition is joined in memory over the budget.
+    let y: Vec<u64> = (0..1000)
+        .select(|p| if p % 10 == 0 { p } else { 7 })
+        .collect();
+    let e: Vec<(u32, u64)> = (0..10u32)
+        .grace_join(
+            y.iter().copied(),
+            |a| *a as u64,
+            |b| *b,
+            |a, b| (*a, *b),
+            100,
+            &dir,
+        )
+        .unwrap()
+        .map(|p| p.unwrap())
+        .collect();
+    assert_eq!(e.len(), 900 + 1);
+    assert!(e.iter().all(|p| p.0 as u64 == p.1));
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 0);
+
+    std::fs::remove_dir(&dir).unwrap();
+}
+
+#[test]
+fn top_k() {
+    let x = [(2, 'a'), (1, 'b'), (2, 'c'), (1, 'd'), (3, 'e'), (0, 'f')];
+    for count in 0..8 {
+        let e: Vec<_> = x.iter().top_k(count, |p| p.0).collect();
+        let y: Vec<_> = x.iter().order_by_descending(|p| p.0).take(count).collect();
+        assert_eq!(e, y);
+
+        let e: Vec<_> = x.iter().bottom_k(count, |p| p.0).collect();
+        let y: Vec<_> = x.iter().order_by(|p| p.0).take(count).collect();
+        assert_eq!(e, y);
+    }
+
+    let e: Vec<i32> = (0..10_000)
+        .select(|p| (p * 7919) % 10_000)
+        .top_k(3, |p| *p)
+        .collect();
+    assert_eq!(e, vec![9999, 9998, 9997]);
+}
+
 #[test]
 fn where_order() {
     let x = 1..100;
@@ -100,12 +755,157 @@ fn concate() {
     assert!((0..200).eq(e));
 }
 
+#[test]
+fn concate_all() {
+    let x = 0..10;
+    let y = vec![10..20, 20..20, 20..30];
+    let e = x.concate_all(y);
+    assert!((0..30).eq(e));
+
+    let e: Vec<i32> = vec![0]
+        .into_iter()
+        .concate_all(vec![vec![1], vec![], vec![2]])
+        .collect();
+    assert_eq!(e, vec![0, 1, 2]);
+}
+
+#[test]
+fn append_prepend() {
+    use crate::iter::{AppendIterator, PrependIterator};
+
+    let e: Vec<i32> = (1..3).append(3).prepend(0).collect();
+    assert_eq!(e, vec![0, 1, 2, 3]);
+
+    let iter: PrependIterator<AppendIterator<std::ops::Range<i32>, i32>, i32> =
+        (1..3).append(3).prepend(0);
+    assert!(iter.clone().eq(iter));
+}
+
+#[test]
+fn default_if_empty() {
+    let mut iter = (0..0).default_if_empty(5);
+    assert_eq!(iter.size_hint(), (1, Some(1)));
+    assert_eq!(iter.next(), Some(5));
+    assert_eq!(iter.next(), None);
+
+    let e: Vec<i32> = (0..2).default_if_empty(5).collect();
+    assert_eq!(e, vec![0, 1]);
+}
+
+#[test]
+fn merge_sorted() {
+    let x = [(1, 'a'), (3, 'a'), (5, 'a')];
+    let y = [(1, 'b'), (2, 'b'), (5, 'b'), (6, 'b')];
+    let iter = x.iter().merge_sorted(y.iter(), |p| p.0);
+    assert_eq!(iter.size_hint(), (7, Some(7)));
+    let e: Vec<_> = iter.cloned().collect();
+    assert_eq!(
+        e,
+        vec![
+            (1, 'a'),
+            (1, 'b'),
+            (2, 'b'),
+            (3, 'a'),
+            (5, 'a'),
+            (5, 'b'),
+            (6, 'b')
+        ]
+    );
+
+    // Each element's key is computed once, and a clone continues independently.
+    let calls = std::cell::Cell::new(0);
+    let mut iter = x.iter().merge_sorted(y.iter(), |p| {
+        calls.set(calls.get() + 1);
+        p.0
+    });
+    iter.next();
+    let copy = iter.clone();
+    assert_eq!(iter.count(), 6);
+    assert_eq!(calls.get(), 7);
+    assert_eq!(copy.count(), 6);
+}
+
+#[test]
+fn merge_sorted_all() {
+    use crate::iter::merge_sorted_all;
+
+    let shards = vec![
+        vec![(1, 0), (4, 0), (4, 0)],
+        vec![],
+        vec![(0, 2), (4, 2)],
+        vec![(4, 3), (9, 3)],
+    ];
+    let iter = merge_sorted_all(shards, |p| p.0);
+    assert_eq!(iter.size_hint(), (7, Some(7)));
+    let e: Vec<_> = iter.collect();
+    assert_eq!(
+        e,
+        vec![(0, 2), (1, 0), (4, 0), (4, 0), (4, 2), (4, 3), (9, 3)]
+    );
+
+    let e: Vec<i32> = merge_sorted_all(Vec::<Vec<i32>>::new(), |p| *p).collect();
+    assert!(e.is_empty());
+
+    let mut iter = merge_sorted_all(vec![vec![1, 3], vec![2]], |p| *p);
+    iter.next();
+    assert_eq!(iter.clone().collect::<Vec<_>>(), vec![2, 3]);
+    assert_eq!(iter.collect::<Vec<_>>(), vec![2, 3]);
+}
+
 #[test]
 fn aggregate() {
     let x = 0..10;
     assert_eq!(x.clone().aggregate(1, |b, v| b * v), x.clone().product());
 }
 
+#[test]
+fn scan_aggregate() {
+    let e: Vec<i32> = (1..5).scan_aggregate(0, |acc, v| acc + v).collect();
+    assert_eq!(e, vec![1, 3, 6, 10]);
+    assert_eq!((0..0).scan_aggregate(0, |acc, v| acc + v).next(), None);
+}
+
+#[test]
+fn pairwise() {
+    let mut iter = (1..5).pairwise();
+    assert_eq!(iter.size_hint(), (3, Some(3)));
+    assert_eq!(iter.next(), Some((1, 2)));
+    assert_eq!(iter.next(), Some((2, 3)));
+    assert_eq!(iter.next(), Some((3, 4)));
+    assert_eq!(iter.next(), None);
+
+    assert_eq!((0..1).pairwise().next(), None);
+}
+
+#[test]
+fn lag() {
+    let e: Vec<_> = (1..5).lag(2).collect();
+    assert_eq!(e, vec![(1, None), (2, None), (3, Some(1)), (4, Some(2))]);
+
+    let e: Vec<_> = (1..3).lag(0).collect();
+    assert_eq!(e, vec![(1, Some(1)), (2, Some(2))]);
+
+    let e: Vec<_> = (1..3).lag(usize::MAX).collect();
+    assert_eq!(e, vec![(1, None), (2, None)]);
+}
+
+#[test]
+fn lead() {
+    let e: Vec<_> = (1..5).lead(2).collect();
+    assert_eq!(e, vec![(1, Some(3)), (2, Some(4)), (3, None), (4, None)]);
+
+    let e: Vec<_> = (1..3).lead(0).collect();
+    assert_eq!(e, vec![(1, Some(1)), (2, Some(2))]);
+
+    let mut iter = (1..5).lead(5);
+    assert_eq!(iter.size_hint(), (4, Some(4)));
+    assert_eq!(iter.next(), Some((1, None)));
+    assert_eq!(iter.size_hint(), (3, Some(3)));
+
+    let e: Vec<_> = (1..3).lead(usize::MAX).collect();
+    assert_eq!(e, vec![(1, None), (2, None)]);
+}
+
 #[test]
 fn contains() {
     let x = 0..10;
@@ -114,6 +914,40 @@ fn contains() {
     assert!(!x.clone().contains(&10));
 }
 
+#[test]
+fn sequence_equal() {
+    assert!((0..3).sequence_equal(vec![0, 1, 2]));
+    assert!(!(0..3).sequence_equal(0..4));
+    assert!(!(0..4).sequence_equal(0..3));
+    assert!((0..0).sequence_equal(0..0));
+
+    let x = ["a", "B", "c"];
+    let y = ["A", "b", "C"];
+    assert!(x
+        .iter()
+        .sequence_equal_by(&y, |a, b| a.eq_ignore_ascii_case(b)));
+    assert!(x.iter().sequence_equal(&x[..]));
+}
+
+#[test]
+fn first_difference() {
+    use crate::iter::EitherOrBoth;
+
+    assert_eq!((0..3).first_difference(0..3), None);
+    assert_eq!(
+        (0..3).first_difference(vec![0, 5, 2]),
+        Some((1, EitherOrBoth::Both(1, 5)))
+    );
+    assert_eq!(
+        (0..2).first_difference(0..3),
+        Some((2, EitherOrBoth::Right(2)))
+    );
+    assert_eq!(
+        (0..4).first_difference(0..3),
+        Some((3, EitherOrBoth::Left(3)))
+    );
+}
+
 #[test]
 fn reverse() {
     let a = [1, 2, 3];
@@ -163,6 +997,102 @@ fn distict() {
     assert_eq!(iter.next(), None);
 }
 
+#[test]
+fn approx_count_distinct() {
+    use crate::iter::HyperLogLog;
+
+    assert_eq!((0..0).approx_count_distinct(10), 0);
+    assert_eq!((0..10).approx_count_distinct(10), 10);
+
+    let count = (0..200_000)
+        .select(|p| p % 50_000)
+        .approx_count_distinct(14);
+    assert!((49_000..51_000).contains(&count));
+
+    let mut left = HyperLogLog::new(12);
+    left.extend(0..30_000);
+    let mut right = HyperLogLog::new(12);
+    right.extend(20_000..50_000);
+    left.merge(&right);
+    assert!((48_000..52_000).contains(&left.count()));
+
+    // The hash is fixed by the crate, so the estimate is the same in every build.
+    assert_eq!((0..1000u64).approx_count_distinct(8), 1021);
+}
+
+#[test]
+fn approx_quantiles() {
+    use crate::iter::QuantileSketch;
+
+    assert_eq!((0..0).approx_quantiles(100, &[0.5]), None);
+    assert_eq!(
+        (0..10).approx_quantiles(100, &[0.0, 0.5, 1.0]),
+        Some(vec![0, 4, 9])
+    );
+
+    let e = (0..100_000)
+        .select(|p| (p * 7919) % 100_000)
+        .approx_quantiles(200, &[0.1, 0.5, 0.9])
+        .unwrap();
+    assert!((8_000..12_000).contains(&e[0]));
+    assert!((48_000..52_000).contains(&e[1]));
+    assert!((88_000..92_000).contains(&e[2]));
+
+    let mut left = QuantileSketch::new(200);
+    left.extend(0..50_000);
+    let mut right = QuantileSketch::new(200);
+    right.extend(50_000..100_000);
+    left.merge(right);
+    assert_eq!(left.count(), 100_000);
+    assert!((48_000..52_000).contains(left.quantile(0.5).unwrap()));
+    let e = left.quantiles(&[0.5, 0.0]).unwrap();
+    assert_eq!(
+        e,
+        vec![left.quantile(0.5).unwrap(), left.quantile(0.0).unwrap()]
+    );
+    assert_eq!(QuantileSketch::<i32>::new(10).quantiles(&[0.5]), None);
+}
+
+#[test]
+fn sample() {
+    let e: Vec<i32> = (0..100).sample(10, 42).collect();
+    assert_eq!(e.len(), 10);
+    assert_eq!(e, (0..100).sample(10, 42).collect::<Vec<_>>());
+    assert_ne!(e, (0..100).sample(10, 43).collect::<Vec<_>>());
+    assert!(e.iter().distinct().count() == 10);
+
+    assert_eq!((0..3).sample(10, 42).collect::<Vec<_>>(), vec![0, 1, 2]);
+    assert_eq!((0..3).sample(0, 42).next(), None);
+    assert_eq!(
+        (0..5).sample(usize::MAX, 42).collect::<Vec<_>>(),
+        vec![0, 1, 2, 3, 4]
+    );
+
+    // Every element should be chosen about equally often.
+    let mut counts = [0; 10];
+    for seed in 0..10_000 {
+        for item in (0..10).sample(3, seed) {
+            counts[item] += 1;
+        }
+    }
+    assert!(counts.iter().all(|c| (2_700..3_300).contains(c)));
+}
+
+#[test]
+fn shuffle() {
+    let e: Vec<i32> = (0..50).shuffle(1).collect();
+    assert_eq!(e, (0..50).shuffle(1).collect::<Vec<_>>());
+    assert_ne!(e, (0..50).collect::<Vec<_>>());
+    assert!(e.iter().cloned().order_by(|p| *p).eq(0..50));
+}
+
+#[test]
+fn random_element() {
+    assert_eq!((0..0).random_element(5), None);
+    assert_eq!((4..5).random_element(5), Some(4));
+    assert_eq!((0..100).random_element(5), (0..100).random_element(5));
+}
+
 #[test]
 fn union() {
     let a = [1, 2, 3, 2, 3, 4];
@@ -176,4 +1106,616 @@ fn union() {
     assert_eq!(iter.next(), Some(&6));
 
     assert_eq!(iter.next(), None);
+    assert_eq!(iter.next(), None);
+    assert_eq!(iter.size_hint(), (0, Some(0)));
+
+    let iter = a.iter().union(b.iter());
+    assert_eq!(iter.size_hint(), (0, Some(12)));
+    let iter = a.iter().distinct();
+    assert_eq!(iter.size_hint(), (0, Some(6)));
+}
+
+#[test]
+fn zip_with() {
+    let x = 1..4;
+    let y = vec![10, 20, 30, 40];
+    let e: Vec<i32> = x.zip_with(y, |a, b| a + b).collect();
+    assert_eq!(e, vec![11, 22, 33]);
+}
+
+#[test]
+fn zip3() {
+    let e: Vec<_> = (0..3).zip3("ab".chars(), vec![true; 5]).collect();
+    assert_eq!(e, vec![(0, 'a', true), (1, 'b', true)]);
+}
+
+#[test]
+fn zip_longest() {
+    use crate::iter::EitherOrBoth;
+
+    let mut iter = (1..3).zip_longest(5..8);
+    assert_eq!(iter.size_hint(), (3, Some(3)));
+    assert_eq!(iter.next(), Some(EitherOrBoth::Both(1, 5)));
+    assert_eq!(iter.next(), Some(EitherOrBoth::Both(2, 6)));
+    assert_eq!(iter.next(), Some(EitherOrBoth::Right(7)));
+    assert_eq!(iter.next(), None);
+
+    let e: Vec<_> = vec![1].into_iter().zip_longest(vec![4, 5]).collect();
+    assert_eq!(e, vec![EitherOrBoth::Both(1, 4), EitherOrBoth::Right(5)]);
+}
+
+#[test]
+fn window_functions() {
+    let x = [("a", 3), ("b", 5), ("a", 7), ("a", 3), ("b", 2), ("a", 9)];
+
+    let e: Vec<_> = x
+        .iter()
+        .over(|p| p.0)
+        .order_by(|p| p.1)
+        .row_number()
+        .select(|(p, v)| (p.1, v))
+        .collect();
+    assert_eq!(e, vec![(3, 1), (3, 2), (7, 3), (9, 4), (2, 1), (5, 2)]);
+
+    let e: Vec<_> = x
+        .iter()
+        .over(|p| p.0)
+        .order_by(|p| p.1)
+        .rank()
+        .select(|(p, v)| (p.1, v))
+        .collect();
+    assert_eq!(e, vec![(3, 1), (3, 1), (7, 3), (9, 4), (2, 1), (5, 2)]);
+
+    let e: Vec<_> = x
+        .iter()
+        .over(|p| p.0)
+        .order_by(|p| p.1)
+        .dense_rank()
+        .select(|(p, v)| (p.1, v))
+        .collect();
+    assert_eq!(e, vec![(3, 1), (3, 1), (7, 2), (9, 3), (2, 1), (5, 2)]);
+
+    let e: Vec<_> = x
+        .iter()
+        .over(|_| ())
+        .order_by_descending(|p| p.1)
+        .percent_rank()
+        .select(|(p, v)| (p.1, v))
+        .collect();
+    assert_eq!(
+        e,
+        vec![(9, 0.0), (7, 0.2), (5, 0.4), (3, 0.6), (3, 0.6), (2, 1.0)]
+    );
+}
+
+#[test]
+fn window_key_calls() {
+    use crate::iter::{OrderedWindow, PartitionWindow};
+    use std::cell::Cell;
+
+    let calls = Cell::new(0);
+    let key = |p: &i32| {
+        calls.set(calls.get() + 1);
+        -*p
+    };
+    let window: PartitionWindow<_, _> = (0..100).over(|p| p % 3);
+    let ordered: OrderedWindow<i32, _> = window.order_by(key);
+    let e: Vec<usize> = ordered.rank().select(|(_, v)| v).collect();
+    assert_eq!(e.len(), 100);
+    assert_eq!(calls.get(), 100);
+}
+
+#[test]
+fn window_order_matches_order_by() {
+    let x = [(2, 'a'), (1, 'b'), (2, 'c'), (1, 'd'), (3, 'e')];
+    let e: Vec<char> = x
+        .iter()
+        .over(|_| ())
+        .order_by_descending(|p| p.0)
+        .row_number()
+        .select(|(p, _)| p.1)
+        .collect();
+    let y: Vec<char> = x
+        .iter()
+        .order_by_descending(|p| p.0)
+        .select(|p| p.1)
+        .collect();
+    assert_eq!(e, y);
+}
+
+#[test]
+fn ntile() {
+    let e: Vec<usize> = (0..10)
+        .over(|_| ())
+        .order_by(|p| *p)
+        .ntile(4)
+        .select(|(_, v)| v)
+        .collect();
+    assert_eq!(e, vec![1, 1, 1, 2, 2, 2, 3, 3, 4, 4]);
+
+    let e: Vec<usize> = (0..2)
+        .over(|_| ())
+        .order_by(|p| *p)
+        .ntile(4)
+        .select(|(_, v)| v)
+        .collect();
+    assert_eq!(e, vec![1, 2]);
+}
+
+#[test]
+fn window() {
+    let mut windows = (1..6).window(3);
+    assert_eq!(windows.next_window(), Some(&[1, 2, 3][..]));
+    assert_eq!(windows.next_window(), Some(&[2, 3, 4][..]));
+    assert_eq!(windows.next_window(), Some(&[3, 4, 5][..]));
+    assert_eq!(windows.next_window(), None);
+
+    assert_eq!((1..3).window(3).next_window(), None);
+
+    let iter = (1..6).window(1).select(|w| w.to_vec());
+    assert_eq!(iter.size_hint(), (5, Some(5)));
+    let e: Vec<_> = iter.collect();
+    assert_eq!(e, vec![vec![1], vec![2], vec![3], vec![4], vec![5]]);
+}
+
+#[test]
+fn moving_aggregates() {
+    let x = [5, 1, 4, 2, 8, 3, 7];
+
+    let windows: Vec<Vec<i32>> = x.iter().cloned().window(3).select(|w| w.to_vec()).collect();
+    let sums: Vec<i32> = windows.iter().map(|w| w.iter().sum()).collect();
+    let mins: Vec<i32> = windows.iter().map(|w| *w.iter().min().unwrap()).collect();
+    let maxs: Vec<i32> = windows.iter().map(|w| *w.iter().max().unwrap()).collect();
+    let avgs: Vec<i32> = sums.iter().map(|s| s / 3).collect();
+
+    let iter = x.iter().cloned().moving_sum(3);
+    assert_eq!(iter.size_hint(), (5, Some(5)));
+    assert_eq!(iter.collect::<Vec<_>>(), sums);
+    assert_eq!(x.iter().cloned().moving_min(3).collect::<Vec<_>>(), mins);
+    assert_eq!(x.iter().cloned().moving_max(3).collect::<Vec<_>>(), maxs);
+    assert_eq!(
+        x.iter()
+            .cloned()
+            .moving_average::<i32>(3)
+            .collect::<Vec<_>>(),
+        avgs
+    );
+
+    let large = usize::MAX;
+    assert_eq!(x.iter().cloned().window(large).next_window(), None);
+    assert_eq!(x.iter().cloned().moving_sum(large).next(), None);
+    assert_eq!(x.iter().cloned().moving_min(large).next(), None);
+    assert_eq!(x.iter().cloned().moving_max(large).next(), None);
+}
+
+#[test]
+fn combinations() {
+    let iter = (0..5).combinations(3);
+    assert_eq!(iter.size_hint(), (10, Some(10)));
+    let e: Vec<_> = iter.collect();
+    assert_eq!(e.len(), 10);
+    assert_eq!(e[0], vec![0, 1, 2]);
+    assert_eq!(e[1], vec![0, 1, 3]);
+    assert_eq!(e[9], vec![2, 3, 4]);
+
+    assert_eq!((0..3).combinations(0).collect::<Vec<_>>(), vec![vec![]]);
+    assert_eq!((0..3).combinations(4).next(), None);
+    assert_eq!((0..3).combinations(usize::MAX).next(), None);
+
+    // The count no longer fits in a usize, but iteration still works.
+    let mut iter = (0..200).combinations(100);
+    assert_eq!(iter.size_hint(), (usize::MAX, None));
+    assert_eq!(iter.next(), Some((0..100).collect()));
+    assert_eq!(iter.size_hint(), (usize::MAX, None));
+    assert_eq!(
+        (0..3).combinations(3).collect::<Vec<_>>(),
+        vec![vec![0, 1, 2]]
+    );
+}
+
+#[test]
+fn combinations_with_replacement() {
+    let iter = (0..3).combinations_with_replacement(2);
+    assert_eq!(iter.size_hint(), (6, Some(6)));
+    let e: Vec<_> = iter.collect();
+    assert_eq!(
+        e,
+        vec![
+            vec![0, 0],
+            vec![0, 1],
+            vec![0, 2],
+            vec![1, 1],
+            vec![1, 2],
+            vec![2, 2]
+        ]
+    );
+    assert_eq!((0..0).combinations_with_replacement(2).next(), None);
+    assert_eq!((0..0).combinations_with_replacement(0).count(), 1);
+    assert_eq!(
+        (0..0).combinations_with_replacement(usize::MAX).next(),
+        None
+    );
+    assert_eq!(
+        (0..200).combinations_with_replacement(100).size_hint(),
+        (usize::MAX, None)
+    );
+}
+
+#[test]
+fn permutations() {
+    let iter = (0..4).permutations(3);
+    assert_eq!(iter.size_hint(), (24, Some(24)));
+    let e: Vec<_> = iter.collect();
+    assert_eq!(e.len(), 24);
+    assert_eq!(e[0], vec![0, 1, 2]);
+    assert_eq!(e[1], vec![0, 1, 3]);
+    assert_eq!(e[2], vec![0, 2, 1]);
+    assert_eq!(e[23], vec![3, 2, 1]);
+
+    let mut sorted = e.clone();
+    sorted.sort();
+    assert_eq!(e, sorted);
+
+    assert_eq!((0..3).permutations(0).collect::<Vec<_>>(), vec![vec![]]);
+    assert_eq!((0..2).permutations(3).next(), None);
+    assert_eq!((0..2).permutations(usize::MAX).next(), None);
+    assert_eq!((0..100).permutations(50).size_hint(), (usize::MAX, None));
+}
+
+#[test]
+fn powerset() {
+    let mut iter = (0..3).powerset();
+    assert_eq!(iter.size_hint(), (8, Some(8)));
+    iter.next();
+    iter.next();
+    assert_eq!(iter.size_hint(), (6, Some(6)));
+    assert_eq!(iter.count(), 6);
+
+    assert_eq!((0..0).powerset().collect::<Vec<Vec<i32>>>(), vec![vec![]]);
+    assert_eq!((0..10).powerset().count(), 1024);
+    assert_eq!((0..200).powerset().size_hint(), (usize::MAX, None));
+}
+
+#[test]
+fn traverse() {
+    #[derive(Clone)]
+    struct Node {
+        name: &'static str,
+        children: Vec<Node>,
+    }
+
+    let leaf = |name| Node {
+        name,
+        children: vec![],
+    };
+    let forest = [
+        Node {
+            name: "a",
+            children: vec![
+                Node {
+                    name: "b",
+                    children: vec![leaf("c")],
+                },
+                leaf("d"),
+            ],
+        },
+        leaf("e"),
+    ];
+
+    let e: Vec<_> = forest
+        .iter()
+        .traverse_depth_first(|p| p.children.iter())
+        .with_depth()
+        .select(|(d, p)| (d, p.name))
+        .collect();
+    assert_eq!(e, vec![(0, "a"), (1, "b"), (2, "c"), (1, "d"), (0, "e")]);
+
+    let e: Vec<_> = forest
+        .iter()
+        .traverse_breadth_first(|p| p.children.iter())
+        .select(|p| p.name)
+        .collect();
+    assert_eq!(e, vec!["a", "e", "b", "d", "c"]);
+}
+
+#[test]
+fn traverse_graph() {
+    let edges = [vec![1, 2], vec![2], vec![0, 3], vec![]];
+
+    let e: Vec<usize> = (0..1)
+        .traverse_depth_first(|p| edges[*p].clone())
+        .distinct_by(|p| *p)
+        .collect();
+    assert_eq!(e, vec![0, 1, 2, 3]);
+
+    let e: Vec<(usize, usize)> = (0..1)
+        .traverse_breadth_first(|p| edges[*p].clone())
+        .distinct_by(|p| *p)
+        .with_depth()
+        .collect();
+    assert_eq!(e, vec![(0, 0), (1, 1), (1, 2), (2, 3)]);
+
+    let e: Vec<usize> = (0..1)
+        .traverse_depth_first(|p| edges[*p].clone())
+        .take(10)
+        .collect();
+    assert_eq!(e, vec![0, 1, 2, 0, 1, 2, 0, 1, 2, 0]);
+
+    // A clone resumes from the same point, with its own record of visited nodes.
+    let mut iter = (0..1)
+        .traverse_breadth_first(|p| edges[*p].clone())
+        .distinct_by(|p| *p)
+        .with_depth();
+    iter.next();
+    let copy = iter.clone();
+    assert_eq!(iter.collect::<Vec<_>>(), vec![(1, 1), (1, 2), (2, 3)]);
+    assert_eq!(copy.collect::<Vec<_>>(), vec![(1, 1), (1, 2), (2, 3)]);
+
+    let mut iter = (0..1)
+        .traverse_depth_first(|p| edges[*p].clone())
+        .distinct_by(|p| *p);
+    assert_eq!(iter.by_ref().count(), 4);
+    assert_eq!(iter.next(), None);
+}
+
+#[test]
+fn to_tree() {
+    let rows = [
+        ("root", None, 1),
+        ("a", Some("root"), 2),
+        ("b", Some("root"), 3),
+        ("a1", Some("a"), 4),
+        ("other", None, 5),
+        ("a2", Some("a"), 6),
+    ];
+    let tree = rows.iter().to_tree(|p| p.0, |p| p.1).unwrap();
+    assert_eq!(tree.len(), 6);
+
+    let roots: Vec<_> = tree.roots().map(|p| p.value().0).collect();
+    assert_eq!(roots, vec!["root", "other"]);
+
+    let root = tree.node(0);
+    assert!(root.parent().is_none());
+    let subtree: Vec<_> = root.subtree().map(|p| (p.depth(), p.value().0)).collect();
+    assert_eq!(
+        subtree,
+        vec![(0, "root"), (1, "a"), (2, "a1"), (2, "a2"), (1, "b")]
+    );
+
+    let path: Vec<_> = tree.node(5).path_to_root().map(|p| p.value().0).collect();
+    assert_eq!(path, vec!["a2", "a", "root"]);
+    assert_eq!(tree.node_by_id(&"a2").unwrap().index(), 5);
+    assert!(tree.node_by_id(&"missing").is_none());
+
+    let total = root.aggregate(|p, c: Vec<i32>| p.2 + c.iter().sum::<i32>());
+    assert_eq!(total, 1 + 2 + 3 + 4 + 6);
+
+    let names = root.aggregate(|p, c: Vec<String>| format!("{}({})", p.0, c.join(",")));
+    assert_eq!(names, "root(a(a1(),a2()),b())");
+
+    // A deep chain must not overflow the call stack.
+    let chain = (0..100_000usize)
+        .to_tree(|p| *p, |p| p.checked_sub(1))
+        .unwrap();
+    let root = chain.node(0);
+    assert_eq!(
+        root.aggregate(|_, c: Vec<usize>| 1 + c.iter().sum::<usize>()),
+        100_000
+    );
+    assert_eq!(chain.node(99_999).depth(), 99_999);
+}
+
+#[test]
+fn to_tree_errors() {
+    use crate::iter::TreeError;
+
+    let rows = vec![(1, None), (2, Some(9)), (3, Some(8))];
+    assert_eq!(
+        rows.into_iter().to_tree(|p| p.0, |p| p.1).unwrap_err(),
+        TreeError::Orphans(vec![(2, 9), (3, 8)])
+    );
+
+    let rows = vec![(1, None), (1, Some(1))];
+    assert_eq!(
+        rows.into_iter().to_tree(|p| p.0, |p| p.1).unwrap_err(),
+        TreeError::DuplicateId(1)
+    );
+
+    let rows = vec![
+        (1, None),
+        (5, Some(2)),
+        (2, Some(3)),
+        (3, Some(4)),
+        (4, Some(2)),
+    ];
+    assert_eq!(
+        rows.into_iter().to_tree(|p| p.0, |p| p.1).unwrap_err(),
+        TreeError::Cycle(vec![2, 3, 4])
+    );
+}
+
+#[test]
+fn topological_sort() {
+    let x = [
+        (1, vec![]),
+        (2, vec![4]),
+        (3, vec![1]),
+        (4, vec![]),
+        (5, vec![2, 3]),
+    ];
+    let e: Vec<i32> = x
+        .iter()
+        .topological_sort(|p| p.0, |p| p.1.clone())
+        .unwrap()
+        .select(|p| p.0)
+        .collect();
+    assert_eq!(e, vec![1, 3, 4, 2, 5]);
+
+    let e: Vec<i32> = (0..5)
+        .topological_sort(|p| *p, |_| vec![])
+        .unwrap()
+        .collect();
+    assert_eq!(e, vec![0, 1, 2, 3, 4]);
+}
+
+#[test]
+fn topological_sort_errors() {
+    use crate::iter::TopologicalSortError;
+
+    let x = [
+        (1, vec![3]),
+        (2, vec![1]),
+        (3, vec![2]),
+        (4, vec![1]),
+        (5, vec![]),
+    ];
+    let e = x.iter().topological_sort(|p| p.0, |p| p.1.clone());
+    assert_eq!(e.unwrap_err(), TopologicalSortError::Cycle(vec![1, 2, 3]));
+
+    let x = [(1, vec![1])];
+    let e = x.iter().topological_sort(|p| p.0, |p| p.1.clone());
+    assert_eq!(e.unwrap_err(), TopologicalSortError::Cycle(vec![1]));
+
+    let x = [(1, vec![]), (2, vec![7])];
+    let e = x.iter().topological_sort(|p| p.0, |p| p.1.clone());
+    assert_eq!(
+        e.unwrap_err(),
+        TopologicalSortError::MissingDependency(2, 7)
+    );
+
+    let x = [(1, vec![]), (1, vec![])];
+    let e = x.iter().topological_sort(|p| p.0, |p| p.1.clone());
+    assert_eq!(e.unwrap_err(), TopologicalSortError::DuplicateId(1));
+}
+
+#[test]
+fn sorted_sets() {
+    use crate::iter::SortedOperation;
+
+    let a = [1, 1, 2, 4, 4, 5, 7];
+    let b = [0, 1, 4, 4, 6, 7, 7, 8];
+
+    let e: Vec<i32> = a.iter().cloned().sorted_distinct(|p| *p).collect();
+    assert_eq!(e, vec![1, 2, 4, 5, 7]);
+
+    let e: Vec<i32> = a
+        .iter()
+        .cloned()
+        .sorted_union(b.iter().cloned(), |p| *p)
+        .collect();
+    assert_eq!(e, vec![0, 1, 2, 4, 5, 6, 7, 8]);
+
+    let e: Vec<i32> = a
+        .iter()
+        .cloned()
+        .sorted_intersect(b.iter().cloned(), |p| *p)
+        .collect();
+    assert_eq!(e, vec![1, 4, 7]);
+
+    let e: Vec<i32> = a
+        .iter()
+        .cloned()
+        .sorted_except(b.iter().cloned(), |p| *p)
+        .collect();
+    assert_eq!(e, vec![2, 5]);
+
+    let e: Vec<i32> = b
+        .iter()
+        .cloned()
+        .sorted_except(a.iter().cloned(), |p| *p)
+        .collect();
+    assert_eq!(e, vec![0, 6, 8]);
+
+    let e: Vec<i32> = (0..0).sorted_union(0..3, |p| *p).collect();
+    assert_eq!(e, vec![0, 1, 2]);
+    assert_eq!((0..3).sorted_intersect(0..0, |p| *p).next(), None);
+
+    let mut iter = a.iter().cloned().sorted_union(b.iter().cloned(), |p| *p);
+    iter.next();
+    assert_eq!(iter.clone().collect::<Vec<_>>(), vec![1, 2, 4, 5, 6, 7, 8]);
+    assert_eq!(iter.count(), 7);
+    let mut iter = a.iter().cloned().sorted_distinct(|p| *p).validated();
+    iter.next();
+    assert_eq!(iter.clone().count(), 4);
+}
+
+#[test]
+fn sorted_sets_keep_first_source() {
+    let a = [(1, 'a'), (2, 'a')];
+    let b = [(1, 'b'), (3, 'b')];
+    let e: Vec<_> = a.iter().sorted_union(b.iter(), |p| p.0).cloned().collect();
+    assert_eq!(e, vec![(1, 'a'), (2, 'a'), (3, 'b')]);
+    let e: Vec<_> = a
+        .iter()
+        .sorted_intersect(b.iter(), |p| p.0)
+        .cloned()
+        .collect();
+    assert_eq!(e, vec![(1, 'a')]);
+}
+
+#[test]
+fn merge_join() {
+    let a = [(1, 'a'), (1, 'b'), (2, 'c'), (4, 'd'), (5, 'e')];
+    let b = [(0, 'x'), (1, 'y'), (1, 'z'), (4, 'w'), (6, 'v')];
+    let e: Vec<_> = a
+        .iter()
+        .merge_join(b.iter(), |p| p.0, |p| p.0, |x, y| (x.1, y.1))
+        .collect();
+    assert_eq!(
+        e,
+        vec![('a', 'y'), ('a', 'z'), ('b', 'y'), ('b', 'z'), ('d', 'w')]
+    );
+
+    // A clone taken inside a run of equal keys replays the rest of that run.
+    let mut iter = a
+        .iter()
+        .merge_join(b.iter(), |p| p.0, |p| p.0, |x, y| (x.1, y.1));
+    iter.next();
+    let copy: Vec<_> = iter.clone().collect();
+    assert_eq!(copy, e[1..]);
+    assert_eq!(iter.collect::<Vec<_>>(), copy);
+}
+
+#[test]
+fn sorted_validation() {
+    use crate::iter::{SortedOperation, UnsortedError};
+
+    let e: Vec<_> = vec![1, 2, 2, 3]
+        .into_iter()
+        .sorted_distinct(|p| *p)
+        .validated()
+        .collect();
+    assert_eq!(e, vec![Ok(1), Ok(2), Ok(3)]);
+
+    let e: Result<Vec<_>, _> = vec![1, 3, 2]
+        .into_iter()
+        .sorted_distinct(|p| *p)
+        .validated()
+        .collect();
+    assert_eq!(
+        e,
+        Err(UnsortedError {
+            source: 0,
+            position: 2
+        })
+    );
+
+    let e: Result<Vec<_>, _> = (0..5)
+        .sorted_union(vec![1, 4, 2].into_iter(), |p| *p)
+        .validated()
+        .collect();
+    assert_eq!(
+        e,
+        Err(UnsortedError {
+            source: 1,
+            position: 2
+        })
+    );
+
+    let e: Result<Vec<_>, _> = vec![2, 1]
+        .into_iter()
+        .merge_join(vec![1, 2].into_iter(), |p| *p, |p| *p, |a, b| a + b)
+        .validated()
+        .collect();
+    assert!(e.is_err());
 }